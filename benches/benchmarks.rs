@@ -1,12 +1,32 @@
 /// This benchmark is to compare the performance of JSON and a few binary formats.
 use std::iter;
 
-use criterion::{criterion_group, criterion_main, Criterion};
+use criterion::{
+    criterion_group, criterion_main, measurement::WallTime, BenchmarkGroup, BenchmarkId, Criterion,
+    Throughput,
+};
+use serde::{de::DeserializeOwned, Serialize};
 use std::hint::black_box;
 
 use json_vs_bin::{
+    blob_data::OwnedBlobData,
+    columnar_data::{BigColumnarData, SmallColumnarData},
+    compression::Compression,
+    corpus::{self, Corpus, Document},
     data::{BigData, SmallData},
-    formats,
+    float_data::NumericData,
+    formats::{self, Format},
+    generator::{Generator, GeneratorConfig},
+    limits::Limits,
+    ordered_data::{
+        BTreeBigData, BTreeSmallData, EntriesBigData, IndexBigData, IndexSmallData, PairsBigData,
+    },
+    owned_data::{
+        CowBigData, CowBigVectorData, CowSmallData, OwnedBigData, OwnedBigVectorData,
+        OwnedSmallData,
+    },
+    transform::{PackedVectorData, Transforms},
+    tree_data::Node,
     vector_data::{BigVectorData, SmallVectorData},
 };
 
@@ -28,10 +48,102 @@ criterion_group! {
         bson_vector,
         bincode_vector,
         bitcode_vector,
-        postcard_vector
+        postcard_vector,
+        seeded,
+        owned,
+        cow,
+        blob,
+        tree,
+        corpus,
+        numeric,
+        columnar,
+        transformed,
+        ordered,
+        compressed,
+        validation
 }
 criterion_main!(benches);
 
+// Benchmark encoding `$data` and decoding it back as `Vec<$ty>` with every format, in groups named
+// `<format>_<family>`. A macro rather than a function as `$ty` may borrow from the encoded bytes.
+macro_rules! bench_formats {
+    ($c:expr, $family:literal, $id:literal, $data:expr, $ty:ty) => {{
+        let data = $data;
+
+        let dbus = formats::DBus::new();
+        let mut group = $c.benchmark_group(concat!("dbus_", $family));
+        group.bench_function($id, |b| {
+            b.iter(|| {
+                let encoded = dbus.encode(black_box(&data));
+                let decoded: Vec<$ty> = dbus.decode(black_box(&encoded));
+                black_box(decoded);
+            })
+        });
+        group.finish();
+
+        let mut group = $c.benchmark_group(concat!("json_", $family));
+        group.bench_function($id, |b| {
+            b.iter(|| {
+                let encoded = formats::Json::encode(black_box(&data));
+                let decoded: Vec<$ty> = formats::Json::decode(black_box(&encoded));
+                black_box(decoded);
+            })
+        });
+        group.finish();
+
+        let mut group = $c.benchmark_group(concat!("simd_json_", $family));
+        group.bench_function($id, |b| {
+            b.iter(|| {
+                let mut encoded = formats::SimdJson::encode(black_box(&data));
+                let decoded: Vec<$ty> = formats::SimdJson::decode(black_box(&mut encoded));
+                black_box(decoded);
+            })
+        });
+        group.finish();
+
+        let mut group = $c.benchmark_group(concat!("bson_", $family));
+        group.bench_function($id, |b| {
+            b.iter(|| {
+                let encoded = formats::Bson::encode(black_box(&data));
+                let decoded: Vec<$ty> = formats::Bson::decode(black_box(&encoded));
+                black_box(decoded);
+            })
+        });
+        group.finish();
+
+        let bincode = formats::Bincode::new();
+        let mut group = $c.benchmark_group(concat!("bincode_", $family));
+        group.bench_function($id, |b| {
+            b.iter(|| {
+                let encoded = bincode.encode(black_box(&data));
+                let decoded: Vec<$ty> = bincode.decode(black_box(&encoded));
+                black_box(decoded);
+            })
+        });
+        group.finish();
+
+        let mut group = $c.benchmark_group(concat!("bitcode_", $family));
+        group.bench_function($id, |b| {
+            b.iter(|| {
+                let encoded = formats::Bitcode::encode(black_box(&data));
+                let decoded: Vec<$ty> = formats::Bitcode::decode(black_box(&encoded));
+                black_box(decoded);
+            })
+        });
+        group.finish();
+
+        let mut group = $c.benchmark_group(concat!("postcard_", $family));
+        group.bench_function($id, |b| {
+            b.iter(|| {
+                let encoded = formats::Postcard::encode(black_box(&data));
+                let decoded: Vec<$ty> = formats::Postcard::decode(black_box(&encoded));
+                black_box(decoded);
+            })
+        });
+        group.finish();
+    }};
+}
+
 fn dbus(c: &mut Criterion) {
     let dbus = formats::DBus::new();
     let data = iter::repeat_with(BigData::new).take(10).collect::<Vec<_>>();
@@ -449,3 +561,473 @@ fn postcard_vector(c: &mut Criterion) {
     });
     group.finish();
 }
+
+// Varied instances from the seeded generator. The seed is part of the benchmark ID so results can
+// be reproduced by setting `JSON_VS_BIN_SEED`.
+fn seeded(c: &mut Criterion) {
+    let mut generator = Generator::new(GeneratorConfig::from_env().unwrap());
+    let seed = generator.seed();
+    let payloads = generator.generate(10, 10);
+    let big = payloads.big_data();
+    let small = payloads.small_data();
+
+    let dbus = formats::DBus::new();
+    let bincode = formats::Bincode::new();
+
+    let mut group = c.benchmark_group("dbus_seeded");
+    group.bench_with_input(BenchmarkId::new("big", seed), &big, |b, data| {
+        b.iter(|| {
+            let encoded = dbus.encode_big(black_box(data));
+            let decoded = dbus.decode_big(black_box(&encoded));
+            black_box(decoded);
+        })
+    });
+    group.bench_with_input(BenchmarkId::new("small", seed), &small, |b, data| {
+        b.iter(|| {
+            let encoded = dbus.encode_small(black_box(data));
+            let decoded = dbus.decode_small(black_box(&encoded));
+            black_box(decoded);
+        })
+    });
+    group.finish();
+
+    let mut group = c.benchmark_group("json_seeded");
+    group.bench_with_input(BenchmarkId::new("big", seed), &big, |b, data| {
+        b.iter(|| {
+            let encoded = formats::Json::encode_big(black_box(data));
+            let decoded = formats::Json::decode_big(black_box(&encoded));
+            black_box(decoded);
+        })
+    });
+    group.bench_with_input(BenchmarkId::new("small", seed), &small, |b, data| {
+        b.iter(|| {
+            let encoded = formats::Json::encode_small(black_box(data));
+            let decoded = formats::Json::decode_small(black_box(&encoded));
+            black_box(decoded);
+        })
+    });
+    group.finish();
+
+    let mut group = c.benchmark_group("simd_json_seeded");
+    group.bench_with_input(BenchmarkId::new("big", seed), &big, |b, data| {
+        b.iter(|| {
+            let mut encoded = formats::SimdJson::encode_big(black_box(data));
+            let decoded = formats::SimdJson::decode_big(black_box(&mut encoded));
+            black_box(decoded);
+        })
+    });
+    group.bench_with_input(BenchmarkId::new("small", seed), &small, |b, data| {
+        b.iter(|| {
+            let mut encoded = formats::SimdJson::encode_small(black_box(data));
+            let decoded = formats::SimdJson::decode_small(black_box(&mut encoded));
+            black_box(decoded);
+        })
+    });
+    group.finish();
+
+    let mut group = c.benchmark_group("bson_seeded");
+    group.bench_with_input(BenchmarkId::new("big", seed), &big, |b, data| {
+        b.iter(|| {
+            let encoded = formats::Bson::encode_big(black_box(data));
+            let decoded = formats::Bson::decode_big(black_box(&encoded));
+            black_box(decoded);
+        })
+    });
+    group.bench_with_input(BenchmarkId::new("small", seed), &small, |b, data| {
+        b.iter(|| {
+            let encoded = formats::Bson::encode_small(black_box(data));
+            let decoded = formats::Bson::decode_small(black_box(&encoded));
+            black_box(decoded);
+        })
+    });
+    group.finish();
+
+    let mut group = c.benchmark_group("bincode_seeded");
+    group.bench_with_input(BenchmarkId::new("big", seed), &big, |b, data| {
+        b.iter(|| {
+            let encoded = bincode.encode_big(black_box(data));
+            let decoded = bincode.decode_big(black_box(&encoded));
+            black_box(decoded);
+        })
+    });
+    group.bench_with_input(BenchmarkId::new("small", seed), &small, |b, data| {
+        b.iter(|| {
+            let encoded = bincode.encode_small(black_box(data));
+            let decoded = bincode.decode_small(black_box(&encoded));
+            black_box(decoded);
+        })
+    });
+    group.finish();
+
+    let mut group = c.benchmark_group("bitcode_seeded");
+    group.bench_with_input(BenchmarkId::new("big", seed), &big, |b, data| {
+        b.iter(|| {
+            let encoded = formats::Bitcode::encode_big(black_box(data));
+            let decoded = formats::Bitcode::decode_big(black_box(&encoded));
+            black_box(decoded);
+        })
+    });
+    group.bench_with_input(BenchmarkId::new("small", seed), &small, |b, data| {
+        b.iter(|| {
+            let encoded = formats::Bitcode::encode_small(black_box(data));
+            let decoded = formats::Bitcode::decode_small(black_box(&encoded));
+            black_box(decoded);
+        })
+    });
+    group.finish();
+
+    let mut group = c.benchmark_group("postcard_seeded");
+    group.bench_with_input(BenchmarkId::new("big", seed), &big, |b, data| {
+        b.iter(|| {
+            let encoded = formats::Postcard::encode_big(black_box(data));
+            let decoded = formats::Postcard::decode_big(black_box(&encoded));
+            black_box(decoded);
+        })
+    });
+    group.bench_with_input(BenchmarkId::new("small", seed), &small, |b, data| {
+        b.iter(|| {
+            let encoded = formats::Postcard::encode_small(black_box(data));
+            let decoded = formats::Postcard::decode_small(black_box(&encoded));
+            black_box(decoded);
+        })
+    });
+    group.finish();
+}
+
+// `String` fields: every string is allocated on decode, whatever the format.
+fn owned(c: &mut Criterion) {
+    let data = iter::repeat_with(OwnedBigData::new)
+        .take(10)
+        .collect::<Vec<_>>();
+    bench_formats!(c, "owned", "big", data, OwnedBigData);
+
+    let data = iter::repeat_with(OwnedSmallData::new)
+        .take(10)
+        .collect::<Vec<_>>();
+    bench_formats!(c, "owned", "small", data, OwnedSmallData);
+
+    let data = iter::repeat_with(OwnedBigVectorData::new)
+        .take(10)
+        .collect::<Vec<_>>();
+    bench_formats!(c, "owned_vector", "big", data, OwnedBigVectorData);
+}
+
+// `Cow<str>` fields: borrowed where the format allows it, allocated otherwise.
+fn cow(c: &mut Criterion) {
+    let data = iter::repeat_with(CowBigData::new)
+        .take(10)
+        .collect::<Vec<_>>();
+    bench_formats!(c, "cow", "big", data, CowBigData);
+
+    let data = iter::repeat_with(CowSmallData::new)
+        .take(10)
+        .collect::<Vec<_>>();
+    bench_formats!(c, "cow", "small", data, CowSmallData);
+
+    let data = iter::repeat_with(CowBigVectorData::new)
+        .take(10)
+        .collect::<Vec<_>>();
+    bench_formats!(c, "cow_vector", "big", data, CowBigVectorData);
+}
+
+// Raw byte blobs: a 4 KiB one (a thumbnail) and a 1 MiB one (an image). JSON writes each byte as a
+// decimal number.
+fn blob(c: &mut Criterion) {
+    let data = iter::repeat_with(|| OwnedBlobData::new(4 * 1024))
+        .take(10)
+        .collect::<Vec<_>>();
+    bench_formats!(c, "blob", "4KiB", data, OwnedBlobData);
+
+    let data = iter::repeat_with(|| OwnedBlobData::new(1024 * 1024))
+        .take(10)
+        .collect::<Vec<_>>();
+    bench_formats!(c, "blob", "1MiB", data, OwnedBlobData);
+}
+
+// Recursive trees, wide and deep. D-Bus can't describe recursive types, so it's skipped, and the
+// other formats go through the generic `Format` as the macro above needs D-Bus.
+fn tree(c: &mut Criterion) {
+    let mut group = c.benchmark_group("tree");
+    for (shape, tree) in [("8x3", Node::tree(8, 3)), ("chain50", Node::chain(50))] {
+        let data = [tree];
+        for format in Format::ALL {
+            if format.try_encode(&data).is_err() {
+                continue;
+            }
+            group.bench_function(BenchmarkId::new(format.name(), shape), |b| {
+                b.iter(|| {
+                    let encoded = format.try_encode(black_box(&data)).unwrap();
+                    let decoded: Vec<Node> = format.try_decode(black_box(&encoded)).unwrap();
+                    black_box(decoded);
+                })
+            });
+        }
+    }
+    group.finish();
+}
+
+// The standard JSON benchmark documents, if present in the corpus directory. Encode and decode are
+// measured separately with the original file size as throughput, like published JSON benchmarks.
+fn corpus(c: &mut Criterion) {
+    let corpus = Corpus::load(&corpus::dir()).unwrap();
+    if corpus.is_empty() {
+        eprintln!(
+            "Skipping corpus benchmarks: no documents in {}",
+            corpus::dir().display()
+        );
+        return;
+    }
+
+    let mut group = c.benchmark_group("corpus_encode");
+    if let Some(document) = &corpus.canada {
+        bench_encode(&mut group, document);
+    }
+    if let Some(document) = &corpus.citm_catalog {
+        bench_encode(&mut group, document);
+    }
+    if let Some(document) = &corpus.twitter {
+        bench_encode(&mut group, document);
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("corpus_decode");
+    if let Some(document) = &corpus.canada {
+        bench_decode(&mut group, document);
+    }
+    if let Some(document) = &corpus.citm_catalog {
+        bench_decode(&mut group, document);
+    }
+    if let Some(document) = &corpus.twitter {
+        bench_decode(&mut group, document);
+    }
+    group.finish();
+}
+
+fn bench_encode<T>(group: &mut BenchmarkGroup<'_, WallTime>, document: &Document<T>)
+where
+    T: Serialize + formats::DBusSignature + Clone,
+{
+    let data = std::slice::from_ref(&document.data);
+    group.throughput(Throughput::Bytes(document.file_size as u64));
+    for format in Format::ALL {
+        if format.try_encode(data).is_err() {
+            continue;
+        }
+        group.bench_function(BenchmarkId::new(format.name(), document.file_name), |b| {
+            b.iter(|| format.try_encode(black_box(data)).unwrap())
+        });
+    }
+}
+
+fn bench_decode<T>(group: &mut BenchmarkGroup<'_, WallTime>, document: &Document<T>)
+where
+    T: Serialize + DeserializeOwned + formats::DBusSignature + Clone,
+{
+    let data = std::slice::from_ref(&document.data);
+    group.throughput(Throughput::Bytes(document.file_size as u64));
+    for format in Format::ALL {
+        let Ok(encoded) = format.try_encode(data) else {
+            continue;
+        };
+        group.bench_function(BenchmarkId::new(format.name(), document.file_name), |b| {
+            b.iter(|| format.try_decode::<T>(black_box(&encoded)).unwrap())
+        });
+    }
+}
+
+// Full-precision floats: a 1000-value series and a 100×1000 matrix.
+fn numeric(c: &mut Criterion) {
+    let data = vec![NumericData::new(1000)];
+    bench_formats!(c, "numeric", "big", data, NumericData);
+}
+
+// Struct-of-arrays forms of the vector payloads, to compare with `<format>_vector`.
+fn columnar(c: &mut Criterion) {
+    let data = iter::repeat_with(BigColumnarData::new)
+        .take(10)
+        .collect::<Vec<_>>();
+    bench_formats!(c, "columnar", "big", data, BigColumnarData);
+
+    let data = iter::repeat_with(SmallColumnarData::new)
+        .take(10)
+        .collect::<Vec<_>>();
+    bench_formats!(c, "columnar", "small", data, SmallColumnarData);
+}
+
+// The big vector payload with every pre-transform applied, to compare with `<format>_owned_vector`.
+// Packing and unpacking aren't included.
+fn transformed(c: &mut Criterion) {
+    let data =
+        iter::repeat_with(|| PackedVectorData::pack(&OwnedBigVectorData::new(), Transforms::ALL))
+            .take(10)
+            .collect::<Vec<_>>();
+    bench_formats!(c, "transformed", "big", data, PackedVectorData);
+}
+
+// `BTreeMap` and `IndexMap` instead of `HashMap`, to compare with `<format>` for the cost of
+// deterministic output, and sequences of pairs or entry structs instead of maps.
+fn ordered(c: &mut Criterion) {
+    let data = iter::repeat_with(BTreeBigData::new)
+        .take(10)
+        .collect::<Vec<_>>();
+    bench_formats!(c, "btree", "big", data, BTreeBigData);
+
+    let data = iter::repeat_with(BTreeSmallData::new)
+        .take(10)
+        .collect::<Vec<_>>();
+    bench_formats!(c, "btree", "small", data, BTreeSmallData);
+
+    let data = iter::repeat_with(IndexBigData::new)
+        .take(10)
+        .collect::<Vec<_>>();
+    bench_formats!(c, "indexmap", "big", data, IndexBigData);
+
+    let data = iter::repeat_with(IndexSmallData::new)
+        .take(10)
+        .collect::<Vec<_>>();
+    bench_formats!(c, "indexmap", "small", data, IndexSmallData);
+
+    let data = iter::repeat_with(PairsBigData::new)
+        .take(10)
+        .collect::<Vec<_>>();
+    bench_formats!(c, "pairs", "big", data, PairsBigData);
+
+    let data = iter::repeat_with(EntriesBigData::new)
+        .take(10)
+        .collect::<Vec<_>>();
+    bench_formats!(c, "entries", "big", data, EntriesBigData);
+}
+
+// Encode + compress and decompress + decode of the seeded big payload with every format and every
+// compression in `JSON_VS_BIN_COMPRESSION`, in groups `compress` and `decompress`.
+fn compressed(c: &mut Criterion) {
+    let compressions = Compression::from_env().unwrap();
+    let mut generator = Generator::new(GeneratorConfig::from_env().unwrap());
+    let payloads = generator.generate(10, 0);
+    let data = payloads.owned_big_data();
+
+    let mut group = c.benchmark_group("compress");
+    for format in Format::ALL {
+        for compression in &compressions {
+            let id = BenchmarkId::new(format.name(), compression);
+            group.bench_function(id, |b| {
+                b.iter(|| black_box(compression.try_encode(format, black_box(data)).unwrap()))
+            });
+        }
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("decompress");
+    for format in Format::ALL {
+        for compression in &compressions {
+            let encoded = compression.try_encode(format, data).unwrap();
+            let id = BenchmarkId::new(format.name(), compression);
+            group.bench_function(id, |b| {
+                b.iter(|| {
+                    let decoded: Vec<OwnedBigData> =
+                        compression.try_decode(format, black_box(&encoded)).unwrap();
+                    black_box(decoded);
+                })
+            });
+        }
+    }
+    group.finish();
+}
+
+// Benchmark decoding the JSON encoding of `$data` as `Vec<$ty>`, as trusted input (its UTF-8 not
+// checked) and untrusted input, in group `validation_$name`. Then decoding it with and without the
+// default `Limits` with every format whose decoder the limits wrap, in group
+// `limits_overhead_$name`. A macro for the same reason as `bench_formats`.
+macro_rules! bench_validation {
+    ($c:expr, $name:literal, $data:expr, $ty:ty) => {{
+        let data = $data;
+        let limits = Limits::default();
+
+        let mut group = $c.benchmark_group(concat!("validation_", $name));
+        let encoded = formats::Json::encode(&data);
+        group.bench_function(BenchmarkId::new(Format::Json.name(), "trusted"), |b| {
+            b.iter(|| {
+                // SAFETY: serde_json writes valid UTF-8.
+                let decoded: Vec<$ty> =
+                    unsafe { formats::Json::try_decode_trusted(black_box(&encoded)) }.unwrap();
+                black_box(decoded)
+            })
+        });
+        group.bench_function(BenchmarkId::new(Format::Json.name(), "untrusted"), |b| {
+            b.iter(|| black_box(formats::Json::decode::<$ty>(black_box(&encoded))))
+        });
+        group.finish();
+
+        let mut group = $c.benchmark_group(concat!("limits_overhead_", $name));
+        group.bench_function(BenchmarkId::new(Format::Json.name(), "plain"), |b| {
+            b.iter(|| black_box(formats::Json::decode::<$ty>(black_box(&encoded))))
+        });
+        group.bench_function(BenchmarkId::new(Format::Json.name(), "limited"), |b| {
+            b.iter(|| {
+                let decoded: Vec<$ty> =
+                    formats::Json::try_decode_with_limits(black_box(&encoded), &limits).unwrap();
+                black_box(decoded)
+            })
+        });
+
+        // SIMD-JSON parses in place, so each iteration decodes a copy.
+        let encoded = formats::SimdJson::encode(&data);
+        group.bench_function(BenchmarkId::new(Format::SimdJson.name(), "plain"), |b| {
+            b.iter(|| {
+                let mut buf = encoded.clone();
+                let decoded: Vec<$ty> = formats::SimdJson::decode(black_box(&mut buf));
+                black_box(decoded);
+            })
+        });
+        group.bench_function(BenchmarkId::new(Format::SimdJson.name(), "limited"), |b| {
+            b.iter(|| {
+                let mut buf = encoded.clone();
+                let decoded: Vec<$ty> =
+                    formats::SimdJson::try_decode_with_limits(black_box(&mut buf), &limits)
+                        .unwrap();
+                black_box(decoded);
+            })
+        });
+
+        let bincode = formats::Bincode::new();
+        let encoded = bincode.encode(&data);
+        group.bench_function(BenchmarkId::new(Format::Bincode.name(), "plain"), |b| {
+            b.iter(|| black_box(bincode.decode::<$ty>(black_box(&encoded))))
+        });
+        group.bench_function(BenchmarkId::new(Format::Bincode.name(), "limited"), |b| {
+            b.iter(|| {
+                let decoded: Vec<$ty> = bincode
+                    .try_decode_with_limits(black_box(&encoded), &limits)
+                    .unwrap();
+                black_box(decoded)
+            })
+        });
+
+        let encoded = formats::Postcard::encode(&data);
+        group.bench_function(BenchmarkId::new(Format::Postcard.name(), "plain"), |b| {
+            b.iter(|| black_box(formats::Postcard::decode::<$ty>(black_box(&encoded))))
+        });
+        group.bench_function(BenchmarkId::new(Format::Postcard.name(), "limited"), |b| {
+            b.iter(|| {
+                let decoded: Vec<$ty> =
+                    formats::Postcard::try_decode_with_limits(black_box(&encoded), &limits)
+                        .unwrap();
+                black_box(decoded)
+            })
+        });
+        group.finish();
+    }};
+}
+
+// Decoding alone, in groups `validation_big`, `validation_big_vector`, `limits_overhead_big` and
+// `limits_overhead_big_vector`. Only JSON has checks to skip (UTF-8), and D-Bus, BSON and Bitcode
+// only get the size check from the limits, so they're left out.
+fn validation(c: &mut Criterion) {
+    let data = iter::repeat_with(BigData::new).take(10).collect::<Vec<_>>();
+    bench_validation!(c, "big", data, BigData);
+
+    let data = iter::repeat_with(BigVectorData::new)
+        .take(10)
+        .collect::<Vec<_>>();
+    bench_validation!(c, "big_vector", data, BigVectorData);
+}
@@ -0,0 +1,159 @@
+/// This benchmark sweeps the payload size to see where the format rankings cross over.
+///
+/// Criterion plots each group as one curve per format. The top of the range takes a long time and a
+/// lot of memory, so by default only counts up to 10,000 are run. Set `JSON_VS_BIN_SWEEP_MAX` to
+/// change that.
+use std::{env, iter};
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use std::hint::black_box;
+
+use json_vs_bin::{data::SmallData, formats, sweep, vector_data::BigVectorData};
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default()
+        .measurement_time(std::time::Duration::from_secs(10))
+        .sample_size(10);
+    targets = small, big_vector
+}
+criterion_main!(benches);
+
+fn max_count() -> usize {
+    env::var(sweep::MAX_COUNT_ENV)
+        .ok()
+        .and_then(|max| max.parse().ok())
+        .unwrap_or(10_000)
+}
+
+fn small(c: &mut Criterion) {
+    let dbus = formats::DBus::new();
+    let bincode = formats::Bincode::new();
+
+    let mut group = c.benchmark_group("sweep_small");
+    for count in sweep::SMALL_COUNTS
+        .into_iter()
+        .filter(|c| *c <= max_count())
+    {
+        let data = iter::repeat_with(SmallData::new)
+            .take(count)
+            .collect::<Vec<_>>();
+        group.throughput(Throughput::Elements(count as u64));
+
+        group.bench_with_input(BenchmarkId::new("json", count), &data, |b, data| {
+            b.iter(|| {
+                let encoded = formats::Json::encode_small(black_box(data));
+                let decoded = formats::Json::decode_small(black_box(&encoded));
+                black_box(decoded);
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("simd_json", count), &data, |b, data| {
+            b.iter(|| {
+                let mut encoded = formats::SimdJson::encode_small(black_box(data));
+                let decoded = formats::SimdJson::decode_small(black_box(&mut encoded));
+                black_box(decoded);
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("dbus", count), &data, |b, data| {
+            b.iter(|| {
+                let encoded = dbus.encode_small(black_box(data));
+                let decoded = dbus.decode_small(black_box(&encoded));
+                black_box(decoded);
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("bson", count), &data, |b, data| {
+            b.iter(|| {
+                let encoded = formats::Bson::encode_small(black_box(data));
+                let decoded = formats::Bson::decode_small(black_box(&encoded));
+                black_box(decoded);
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("bincode", count), &data, |b, data| {
+            b.iter(|| {
+                let encoded = bincode.encode_small(black_box(data));
+                let decoded = bincode.decode_small(black_box(&encoded));
+                black_box(decoded);
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("bitcode", count), &data, |b, data| {
+            b.iter(|| {
+                let encoded = formats::Bitcode::encode_small(black_box(data));
+                let decoded = formats::Bitcode::decode_small(black_box(&encoded));
+                black_box(decoded);
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("postcard", count), &data, |b, data| {
+            b.iter(|| {
+                let encoded = formats::Postcard::encode_small(black_box(data));
+                let decoded = formats::Postcard::decode_small(black_box(&encoded));
+                black_box(decoded);
+            })
+        });
+    }
+    group.finish();
+}
+
+fn big_vector(c: &mut Criterion) {
+    let dbus = formats::DBus::new();
+    let bincode = formats::Bincode::new();
+
+    let mut group = c.benchmark_group("sweep_big_vector");
+    for records in sweep::BIG_VECTOR_RECORDS
+        .into_iter()
+        .filter(|r| *r <= max_count())
+    {
+        let data = [BigVectorData::with_records(records)];
+        group.throughput(Throughput::Elements(records as u64));
+
+        group.bench_with_input(BenchmarkId::new("json", records), &data, |b, data| {
+            b.iter(|| {
+                let encoded = formats::Json::encode_big_vector(black_box(data));
+                let decoded = formats::Json::decode_big_vector(black_box(&encoded));
+                black_box(decoded);
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("simd_json", records), &data, |b, data| {
+            b.iter(|| {
+                let mut encoded = formats::SimdJson::encode_big_vector(black_box(data));
+                let decoded = formats::SimdJson::decode_big_vector(black_box(&mut encoded));
+                black_box(decoded);
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("dbus", records), &data, |b, data| {
+            b.iter(|| {
+                let encoded = dbus.encode_big_vector(black_box(data));
+                let decoded = dbus.decode_big_vector(black_box(&encoded));
+                black_box(decoded);
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("bson", records), &data, |b, data| {
+            b.iter(|| {
+                let encoded = formats::Bson::encode_big_vector(black_box(data));
+                let decoded = formats::Bson::decode_big_vector(black_box(&encoded));
+                black_box(decoded);
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("bincode", records), &data, |b, data| {
+            b.iter(|| {
+                let encoded = bincode.encode_big_vector(black_box(data));
+                let decoded = bincode.decode_big_vector(black_box(&encoded));
+                black_box(decoded);
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("bitcode", records), &data, |b, data| {
+            b.iter(|| {
+                let encoded = formats::Bitcode::encode_big_vector(black_box(data));
+                let decoded = formats::Bitcode::decode_big_vector(black_box(&encoded));
+                black_box(decoded);
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("postcard", records), &data, |b, data| {
+            b.iter(|| {
+                let encoded = formats::Postcard::encode_big_vector(black_box(data));
+                let decoded = formats::Postcard::decode_big_vector(black_box(&encoded));
+                black_box(decoded);
+            })
+        });
+    }
+    group.finish();
+}
@@ -0,0 +1,5 @@
+#![no_main]
+use json_vs_bin::formats::Format;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|bytes: &[u8]| json_vs_bin_fuzz::decode(Format::Bincode, bytes));
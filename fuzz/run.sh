@@ -0,0 +1,21 @@
+#!/bin/sh
+# Run the fuzz targets for a fixed time each, starting from valid encodings of every payload.
+#
+# Usage: fuzz/run.sh [SECONDS] [TARGET...]
+#
+# SECONDS defaults to 60 per target, and all targets run if none are given. Inputs taking more than
+# 10 s to decode count as hangs, and allocating more than 512 MB at once or 2 GB in total counts as
+# excessive. Crashing inputs are saved in fuzz/artifacts/<target>/.
+set -eu
+
+cd "$(dirname "$0")"
+seconds=${1:-60}
+[ $# -gt 0 ] && shift
+targets=${*:-json simd_json dbus bson bincode bitcode postcard}
+
+cargo run --release --quiet --bin seed_corpus
+for target in $targets; do
+    echo "=== $target ==="
+    cargo +nightly fuzz run "$target" -- \
+        -max_total_time="$seconds" -timeout=10 -malloc_limit_mb=512 -rss_limit_mb=2048
+done
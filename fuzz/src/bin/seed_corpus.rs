@@ -0,0 +1,13 @@
+//! Write valid encodings of every payload to `corpus/<target>/`, where `cargo fuzz run` starts.
+use std::{fs, path::Path};
+
+fn main() {
+    let corpus = Path::new(env!("CARGO_MANIFEST_DIR")).join("corpus");
+    for (format, target) in json_vs_bin_fuzz::TARGETS {
+        let dir = corpus.join(target);
+        fs::create_dir_all(&dir).unwrap();
+        for (name, encoded) in json_vs_bin_fuzz::seeds(format) {
+            fs::write(dir.join(name), encoded).unwrap();
+        }
+    }
+}
@@ -0,0 +1,53 @@
+//! Shared code of the fuzz targets, one per format.
+//!
+//! The targets go through [`Encoded::try_decode`], the decode path the tests share, which makes the
+//! same library calls as the `decode_*` functions but returns malformed input errors instead of
+//! unwrapping them, so only panics (and hangs and runaway allocations, caught by libFuzzer) are
+//! reported.
+use json_vs_bin::{
+    data::{BigData, SmallData},
+    formats::{Encoded, Error, Format},
+    generator::{Generator, GeneratorConfig},
+    vector_data::{BigVectorData, SmallVectorData},
+};
+
+/// The fuzz target of each format.
+pub const TARGETS: [(Format, &str); 7] = [
+    (Format::Json, "json"),
+    (Format::SimdJson, "simd_json"),
+    (Format::DBus, "dbus"),
+    (Format::Bson, "bson"),
+    (Format::Bincode, "bincode"),
+    (Format::Bitcode, "bitcode"),
+    (Format::Postcard, "postcard"),
+];
+
+/// Decode `bytes` with `format` as every payload type, as the benchmarks do (zero-copy).
+pub fn decode(format: Format, bytes: &[u8]) {
+    let mut encoded = Encoded::new(format, bytes.to_vec());
+    let _ = encoded.try_decode::<BigData>().map(drop);
+    let _ = encoded.try_decode::<SmallData>().map(drop);
+    let _ = encoded.try_decode::<BigVectorData>().map(drop);
+    let _ = encoded.try_decode::<SmallVectorData>().map(drop);
+}
+
+/// Valid encodings of every payload type with `format`, to start fuzzing from, by name.
+pub fn seeds(format: Format) -> Vec<(String, Vec<u8>)> {
+    let payloads = Generator::new(GeneratorConfig::from_env().unwrap()).generate(2, 2);
+    let mut seeds = vec![];
+    let mut add = |name: &str, encoded: Result<Vec<u8>, Error>| {
+        if let Ok(encoded) = encoded {
+            seeds.push((name.to_string(), encoded));
+        }
+    };
+
+    add("big", format.try_encode(&[BigData::new()]));
+    add("small", format.try_encode(&[SmallData::new()]));
+    add("big_vector", format.try_encode(&[BigVectorData::new()]));
+    add("small_vector", format.try_encode(&[SmallVectorData::new()]));
+    add("seeded_big", format.try_encode(&payloads.big_data()));
+    add("seeded_small", format.try_encode(&payloads.small_data()));
+    add("empty", format.try_encode::<SmallData>(&[]));
+
+    seeds
+}
@@ -0,0 +1,186 @@
+//! Statistics on the encoded bytes, to see why some formats compress better than others: how
+//! evenly the byte values are spread, how much is zero padding or small integers, and how long the
+//! repeated runs a compressor can reference are.
+use crate::{
+    compression::{Codec, Compression},
+    formats::{DBusSignature, Error, Format},
+};
+use serde::Serialize;
+
+/// The compressor the compression ratio is measured with.
+pub const REFERENCE_COMPRESSION: Compression = Compression {
+    codec: Codec::Zstd,
+    level: Some(3),
+};
+
+/// Number of repeated substrings reported per output.
+pub const REPEATS: usize = 3;
+
+#[derive(Debug)]
+pub struct Analysis {
+    pub size: usize,
+    /// Shannon entropy of the byte values, in bits per byte (0 to 8).
+    pub entropy: f64,
+    /// Fraction of the bytes that are zero.
+    pub zeros: f64,
+    /// The longest substrings occurring at least twice, longest first, none overlapping another.
+    pub repeats: Vec<Repeat>,
+    /// Size compressed with [`REFERENCE_COMPRESSION`] divided by the size.
+    pub compression_ratio: f64,
+}
+
+/// A substring found at two offsets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Repeat {
+    pub offset: usize,
+    pub other_offset: usize,
+    pub len: usize,
+}
+
+impl Repeat {
+    fn overlaps(&self, other: &Repeat) -> bool {
+        let ranges = |r: &Repeat| {
+            [
+                r.offset..r.offset + r.len,
+                r.other_offset..r.other_offset + r.len,
+            ]
+        };
+        ranges(self).iter().any(|a| {
+            ranges(other)
+                .iter()
+                .any(|b| a.start < b.end && b.start < a.end)
+        })
+    }
+}
+
+pub fn analyze(bytes: &[u8]) -> Result<Analysis, Error> {
+    let compressed = REFERENCE_COMPRESSION.compress(bytes)?;
+
+    Ok(Analysis {
+        size: bytes.len(),
+        entropy: entropy(bytes),
+        zeros: bytes.iter().filter(|b| **b == 0).count() as f64 / bytes.len().max(1) as f64,
+        repeats: longest_repeats(bytes, REPEATS),
+        compression_ratio: compressed.len() as f64 / bytes.len().max(1) as f64,
+    })
+}
+
+/// Encode `data` with every format and analyze the output.
+pub fn report<T>(data: &[T]) -> Vec<(&'static str, Result<Analysis, Error>)>
+where
+    T: Serialize + DBusSignature + Clone,
+{
+    Format::ALL
+        .iter()
+        .map(|format| {
+            let analysis = format.try_encode(data).and_then(|bytes| analyze(&bytes));
+            (format.name(), analysis)
+        })
+        .collect()
+}
+
+/// Shannon entropy of the byte values of `bytes`, in bits per byte.
+pub fn entropy(bytes: &[u8]) -> f64 {
+    let mut counts = [0usize; 256];
+    for byte in bytes {
+        counts[*byte as usize] += 1;
+    }
+
+    counts
+        .iter()
+        .filter(|count| **count > 0)
+        .map(|count| {
+            let p = *count as f64 / bytes.len() as f64;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+/// The `count` longest repeated substrings of `bytes` that don't overlap each other, from the
+/// longest common prefixes of neighbouring suffixes in the suffix array.
+pub fn longest_repeats(bytes: &[u8], count: usize) -> Vec<Repeat> {
+    let suffixes = suffix_array(bytes);
+    let lcp = lcp_array(bytes, &suffixes);
+
+    let mut candidates = (1..suffixes.len())
+        .filter(|i| lcp[*i] > 0)
+        .map(|i| Repeat {
+            offset: suffixes[i - 1].min(suffixes[i]),
+            other_offset: suffixes[i - 1].max(suffixes[i]),
+            len: lcp[i],
+        })
+        .collect::<Vec<_>>();
+    candidates.sort_by(|a, b| b.len.cmp(&a.len).then(a.offset.cmp(&b.offset)));
+
+    // Every suffix of a repeat is a repeat too, so skip those overlapping one already taken.
+    let mut repeats: Vec<Repeat> = vec![];
+    for candidate in candidates {
+        if repeats.len() == count {
+            break;
+        }
+        if !repeats.iter().any(|repeat| repeat.overlaps(&candidate)) {
+            repeats.push(candidate);
+        }
+    }
+
+    repeats
+}
+
+// Suffix array by prefix doubling: sort by the ranks of the first k bytes, then 2k, until every
+// rank is distinct.
+fn suffix_array(bytes: &[u8]) -> Vec<usize> {
+    let n = bytes.len();
+    let mut suffixes = (0..n).collect::<Vec<_>>();
+    let mut rank = bytes.iter().map(|b| *b as usize).collect::<Vec<_>>();
+    let mut next = vec![0; n];
+    let mut k = 1;
+    if n < 2 {
+        return suffixes;
+    }
+
+    loop {
+        let key = |i: usize| (rank[i], rank.get(i + k).map_or(0, |r| r + 1));
+        suffixes.sort_unstable_by_key(|i| key(*i));
+
+        next[suffixes[0]] = 0;
+        for i in 1..n {
+            next[suffixes[i]] =
+                next[suffixes[i - 1]] + usize::from(key(suffixes[i - 1]) < key(suffixes[i]));
+        }
+        std::mem::swap(&mut rank, &mut next);
+
+        if rank[suffixes[n - 1]] == n - 1 {
+            break;
+        }
+        k *= 2;
+    }
+
+    suffixes
+}
+
+// Kasai's algorithm: `lcp[i]` is the length of the common prefix of suffixes `i - 1` and `i`.
+fn lcp_array(bytes: &[u8], suffixes: &[usize]) -> Vec<usize> {
+    let n = bytes.len();
+    let mut rank = vec![0; n];
+    for (i, suffix) in suffixes.iter().enumerate() {
+        rank[*suffix] = i;
+    }
+
+    let mut lcp = vec![0; n];
+    let mut len = 0;
+    for suffix in 0..n {
+        if rank[suffix] == 0 {
+            len = 0;
+            continue;
+        }
+        let previous = suffixes[rank[suffix] - 1];
+        while suffix + len < n && previous + len < n && bytes[suffix + len] == bytes[previous + len]
+        {
+            len += 1;
+        }
+        lcp[rank[suffix]] = len;
+        len = len.saturating_sub(1);
+    }
+
+    lcp
+}
@@ -0,0 +1,128 @@
+//! Payloads carrying raw binary blobs (hashes, signatures, images, encrypted data), serialized
+//! through [`serde_bytes`] so that formats with a native byte string type can use it.
+//!
+//! JSON has no such type: `serde_json` writes bytes as an array of decimal numbers, one per byte.
+use crate::{
+    formats::{self, Error, Format},
+    generator::DEFAULT_SEED,
+    measure,
+};
+use rand::{RngCore, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+use std::{iter, time::Duration};
+use zvariant::Type;
+
+/// Sizes of the `content` blob in the report, from a small token up to an image.
+pub const BLOB_SIZES: [usize; 5] = [16, 256, 4 * 1024, 64 * 1024, 1024 * 1024];
+
+/// Payload with borrowed blobs, for zero-copy decoding.
+#[derive(Deserialize, Serialize, Type, PartialEq, Debug, Clone)]
+pub struct BlobData<'a> {
+    pub id: u64,
+    pub mime_type: &'a str,
+    #[serde(borrow, with = "serde_bytes")]
+    pub sha256: &'a [u8],
+    #[serde(borrow, with = "serde_bytes")]
+    pub signature: &'a [u8],
+    #[serde(borrow, with = "serde_bytes")]
+    pub content: &'a [u8],
+}
+
+/// [`BlobData`] owning its blobs.
+#[derive(Deserialize, Serialize, Type, PartialEq, Debug, Clone)]
+pub struct OwnedBlobData {
+    pub id: u64,
+    pub mime_type: String,
+    #[serde(with = "serde_bytes")]
+    pub sha256: Vec<u8>,
+    #[serde(with = "serde_bytes")]
+    pub signature: Vec<u8>,
+    #[serde(with = "serde_bytes")]
+    pub content: Vec<u8>,
+}
+
+impl OwnedBlobData {
+    /// A payload with a `content` blob of `size` bytes.
+    ///
+    /// The blobs are random and so incompressible, like encrypted or already compressed data.
+    pub fn new(size: usize) -> Self {
+        let mut rng = ChaCha8Rng::seed_from_u64(DEFAULT_SEED);
+        let mut bytes = |len| {
+            let mut bytes = vec![0; len];
+            rng.fill_bytes(&mut bytes);
+            bytes
+        };
+
+        Self {
+            id: 1234567890,
+            mime_type: "application/octet-stream".to_string(),
+            sha256: bytes(32),
+            signature: bytes(64),
+            content: bytes(size),
+        }
+    }
+
+    pub fn as_borrowed(&self) -> BlobData<'_> {
+        BlobData {
+            id: self.id,
+            mime_type: &self.mime_type,
+            sha256: &self.sha256,
+            signature: &self.signature,
+            content: &self.content,
+        }
+    }
+}
+
+/// How one format copes with one blob size.
+#[derive(Debug)]
+pub struct BlobResult {
+    pub format: &'static str,
+    /// Size of the `content` blob.
+    pub blob_size: usize,
+    /// Encoded size of a single payload.
+    pub encoded_size: Result<usize, Error>,
+    /// Whether the payload round-trips as borrowed [`BlobData`].
+    pub borrowed: Result<(), Error>,
+    /// Mean encode + decode time of 10 payloads as [`OwnedBlobData`].
+    pub owned: Result<Duration, Error>,
+}
+
+/// Run payloads with a `blob_size` bytes blob through every format.
+pub fn report(blob_size: usize) -> Vec<BlobResult> {
+    let owned = iter::repeat_with(|| OwnedBlobData::new(blob_size))
+        .take(10)
+        .collect::<Vec<_>>();
+    let borrowed = owned
+        .iter()
+        .map(OwnedBlobData::as_borrowed)
+        .collect::<Vec<_>>();
+
+    Format::ALL
+        .into_iter()
+        .map(|f| {
+            let borrowed = f.try_roundtrip_borrowed(&borrowed, |encoded| {
+                Ok(encoded.try_decode::<BlobData>()? == borrowed)
+            });
+            let encoded_size = f.try_encode(&owned[..1]).map(|encoded| encoded.len());
+            let owned = f
+                .try_roundtrip(&owned)
+                .map(|()| measure::time(|| f.try_roundtrip(&owned)));
+
+            BlobResult {
+                format: f.name(),
+                blob_size,
+                encoded_size,
+                borrowed,
+                owned,
+            }
+        })
+        .collect()
+}
+
+/// The start of the JSON encoding of a payload, showing how bytes are represented.
+pub fn json_sample() -> String {
+    let json = formats::Json::encode(&[OwnedBlobData::new(16)]);
+
+    String::from_utf8_lossy(&json).into_owned()
+}
@@ -0,0 +1,329 @@
+//! Struct-of-arrays counterparts of [`BigVectorData`] and [`SmallVectorData`]: one array per field
+//! instead of one struct per record, so self-describing formats write each field name once per
+//! payload rather than once per record.
+use crate::{
+    formats::{Error, Format},
+    vector_data::{
+        BigVectorData, LogEvent, MarketTick, Metadata, SensorReading, SmallVectorData, Summary,
+    },
+};
+use serde::{Deserialize, Serialize};
+use zvariant::Type;
+
+/// [`SensorReading`]s as columns. All columns have the same length.
+#[derive(Deserialize, Serialize, Type, PartialEq, Debug, Clone, Default)]
+pub struct SensorColumns {
+    pub timestamps: Vec<u64>,
+    pub sensor_ids: Vec<u32>,
+    pub temperatures: Vec<f32>,
+    pub humidities: Vec<f32>,
+    pub pressures: Vec<f32>,
+    pub batteries: Vec<u8>,
+    pub statuses: Vec<u8>,
+}
+
+impl From<&[SensorReading]> for SensorColumns {
+    fn from(readings: &[SensorReading]) -> Self {
+        let mut columns = Self::default();
+        for reading in readings {
+            columns.timestamps.push(reading.timestamp);
+            columns.sensor_ids.push(reading.sensor_id);
+            columns.temperatures.push(reading.temperature);
+            columns.humidities.push(reading.humidity);
+            columns.pressures.push(reading.pressure);
+            columns.batteries.push(reading.battery);
+            columns.statuses.push(reading.status);
+        }
+
+        columns
+    }
+}
+
+impl SensorColumns {
+    /// The readings back as rows, or an error if the columns differ in length.
+    pub fn rows(&self) -> Result<Vec<SensorReading>, Error> {
+        let len = column_len(&[
+            self.timestamps.len(),
+            self.sensor_ids.len(),
+            self.temperatures.len(),
+            self.humidities.len(),
+            self.pressures.len(),
+            self.batteries.len(),
+            self.statuses.len(),
+        ])?;
+
+        Ok((0..len)
+            .map(|i| SensorReading {
+                timestamp: self.timestamps[i],
+                sensor_id: self.sensor_ids[i],
+                temperature: self.temperatures[i],
+                humidity: self.humidities[i],
+                pressure: self.pressures[i],
+                battery: self.batteries[i],
+                status: self.statuses[i],
+            })
+            .collect())
+    }
+}
+
+/// [`MarketTick`]s as columns. All columns have the same length.
+#[derive(Deserialize, Serialize, Type, PartialEq, Debug, Clone, Default)]
+pub struct MarketColumns<'a> {
+    pub timestamps: Vec<u64>,
+    #[serde(borrow)]
+    pub symbols: Vec<&'a str>,
+    pub bids: Vec<f64>,
+    pub asks: Vec<f64>,
+    pub lasts: Vec<f64>,
+    pub volumes: Vec<u64>,
+    pub bid_sizes: Vec<u32>,
+    pub ask_sizes: Vec<u32>,
+}
+
+impl<'a> From<&[MarketTick<'a>]> for MarketColumns<'a> {
+    fn from(ticks: &[MarketTick<'a>]) -> Self {
+        let mut columns = Self::default();
+        for tick in ticks {
+            columns.timestamps.push(tick.timestamp);
+            columns.symbols.push(tick.symbol);
+            columns.bids.push(tick.bid);
+            columns.asks.push(tick.ask);
+            columns.lasts.push(tick.last);
+            columns.volumes.push(tick.volume);
+            columns.bid_sizes.push(tick.bid_size);
+            columns.ask_sizes.push(tick.ask_size);
+        }
+
+        columns
+    }
+}
+
+impl<'a> MarketColumns<'a> {
+    /// The ticks back as rows, or an error if the columns differ in length.
+    pub fn rows(&self) -> Result<Vec<MarketTick<'a>>, Error> {
+        let len = column_len(&[
+            self.timestamps.len(),
+            self.symbols.len(),
+            self.bids.len(),
+            self.asks.len(),
+            self.lasts.len(),
+            self.volumes.len(),
+            self.bid_sizes.len(),
+            self.ask_sizes.len(),
+        ])?;
+
+        Ok((0..len)
+            .map(|i| MarketTick {
+                timestamp: self.timestamps[i],
+                symbol: self.symbols[i],
+                bid: self.bids[i],
+                ask: self.asks[i],
+                last: self.lasts[i],
+                volume: self.volumes[i],
+                bid_size: self.bid_sizes[i],
+                ask_size: self.ask_sizes[i],
+            })
+            .collect())
+    }
+}
+
+/// [`LogEvent`]s as columns. All columns have the same length.
+#[derive(Deserialize, Serialize, Type, PartialEq, Debug, Clone, Default)]
+pub struct LogColumns<'a> {
+    pub timestamps: Vec<u64>,
+    pub levels: Vec<u8>,
+    #[serde(borrow)]
+    pub components: Vec<&'a str>,
+    #[serde(borrow)]
+    pub messages: Vec<&'a str>,
+    pub trace_ids: Vec<u64>,
+    pub span_ids: Vec<u64>,
+    pub user_ids: Vec<u32>,
+}
+
+impl<'a> From<&[LogEvent<'a>]> for LogColumns<'a> {
+    fn from(events: &[LogEvent<'a>]) -> Self {
+        let mut columns = Self::default();
+        for event in events {
+            columns.timestamps.push(event.timestamp);
+            columns.levels.push(event.level);
+            columns.components.push(event.component);
+            columns.messages.push(event.message);
+            columns.trace_ids.push(event.trace_id);
+            columns.span_ids.push(event.span_id);
+            columns.user_ids.push(event.user_id);
+        }
+
+        columns
+    }
+}
+
+impl<'a> LogColumns<'a> {
+    /// The events back as rows, or an error if the columns differ in length.
+    pub fn rows(&self) -> Result<Vec<LogEvent<'a>>, Error> {
+        let len = column_len(&[
+            self.timestamps.len(),
+            self.levels.len(),
+            self.components.len(),
+            self.messages.len(),
+            self.trace_ids.len(),
+            self.span_ids.len(),
+            self.user_ids.len(),
+        ])?;
+
+        Ok((0..len)
+            .map(|i| LogEvent {
+                timestamp: self.timestamps[i],
+                level: self.levels[i],
+                component: self.components[i],
+                message: self.messages[i],
+                trace_id: self.trace_ids[i],
+                span_id: self.span_ids[i],
+                user_id: self.user_ids[i],
+            })
+            .collect())
+    }
+}
+
+#[derive(Deserialize, Serialize, Type, PartialEq, Debug, Clone)]
+pub struct BigColumnarData<'a> {
+    pub sensors: SensorColumns,
+    #[serde(borrow)]
+    pub market: MarketColumns<'a>,
+    #[serde(borrow)]
+    pub logs: LogColumns<'a>,
+    #[serde(borrow)]
+    pub metadata: Metadata<'a>,
+}
+
+impl<'a> BigColumnarData<'a> {
+    /// The columnar form of [`BigVectorData::new`].
+    pub fn new() -> BigColumnarData<'static> {
+        (&BigVectorData::new()).into()
+    }
+}
+
+impl<'a> From<&BigVectorData<'a>> for BigColumnarData<'a> {
+    fn from(data: &BigVectorData<'a>) -> Self {
+        Self {
+            sensors: data.sensors[..].into(),
+            market: data.market[..].into(),
+            logs: data.logs[..].into(),
+            metadata: data.metadata.clone(),
+        }
+    }
+}
+
+impl<'a> TryFrom<&BigColumnarData<'a>> for BigVectorData<'a> {
+    type Error = Error;
+
+    fn try_from(data: &BigColumnarData<'a>) -> Result<Self, Error> {
+        Ok(Self {
+            sensors: data.sensors.rows()?,
+            market: data.market.rows()?,
+            logs: data.logs.rows()?,
+            metadata: data.metadata.clone(),
+        })
+    }
+}
+
+#[derive(Deserialize, Serialize, Type, PartialEq, Debug, Clone)]
+pub struct SmallColumnarData {
+    pub readings: SensorColumns,
+    pub summary: Summary,
+}
+
+impl SmallColumnarData {
+    /// The columnar form of [`SmallVectorData::new`].
+    pub fn new() -> Self {
+        (&SmallVectorData::new()).into()
+    }
+}
+
+impl Default for SmallColumnarData {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl From<&SmallVectorData> for SmallColumnarData {
+    fn from(data: &SmallVectorData) -> Self {
+        Self {
+            readings: data.readings[..].into(),
+            summary: data.summary.clone(),
+        }
+    }
+}
+
+impl TryFrom<&SmallColumnarData> for SmallVectorData {
+    type Error = Error;
+
+    fn try_from(data: &SmallColumnarData) -> Result<Self, Error> {
+        Ok(Self {
+            readings: data.readings.rows()?,
+            summary: data.summary.clone(),
+        })
+    }
+}
+
+/// Encoded sizes of a payload in row and columnar form.
+#[derive(Debug)]
+pub struct Comparison {
+    pub payload: &'static str,
+    /// Whether converting to columns and back gives the original rows.
+    pub lossless: bool,
+    /// Encoded sizes of the rows, in the order of [`Format::ALL`].
+    pub rows: Vec<Result<usize, Error>>,
+    /// Encoded sizes of the columns, in the order of [`Format::ALL`].
+    pub columns: Vec<Result<usize, Error>>,
+}
+
+/// Compare 10 instances of the big and small vector payloads with their columnar forms.
+pub fn report() -> Vec<Comparison> {
+    let big = vec![BigVectorData::new(); 10];
+    let big_columnar = big.iter().map(BigColumnarData::from).collect::<Vec<_>>();
+    let small = vec![SmallVectorData::new(); 10];
+    let small_columnar = small
+        .iter()
+        .map(SmallColumnarData::from)
+        .collect::<Vec<_>>();
+
+    vec![
+        Comparison {
+            payload: "Big",
+            lossless: big_columnar
+                .iter()
+                .map(|data| BigVectorData::try_from(data).ok())
+                .eq(big.iter().cloned().map(Some)),
+            rows: sizes(&big),
+            columns: sizes(&big_columnar),
+        },
+        Comparison {
+            payload: "Small",
+            lossless: small_columnar
+                .iter()
+                .map(|data| SmallVectorData::try_from(data).ok())
+                .eq(small.iter().cloned().map(Some)),
+            rows: sizes(&small),
+            columns: sizes(&small_columnar),
+        },
+    ]
+}
+
+// The length shared by columns of the given lengths. Columns written from rows all have the same
+// length, but decoded ones may not.
+pub(crate) fn column_len(lens: &[usize]) -> Result<usize, Error> {
+    let len = lens[0];
+    if lens.iter().any(|other| *other != len) {
+        return Err("columns differ in length".into());
+    }
+
+    Ok(len)
+}
+
+fn sizes<T: Serialize + Type + Clone>(data: &[T]) -> Vec<Result<usize, Error>> {
+    Format::ALL
+        .iter()
+        .map(|format| Ok(format.try_encode(data)?.len()))
+        .collect()
+}
@@ -0,0 +1,253 @@
+//! General-purpose compression applied on top of any format's output, as payloads usually are
+//! before going over the network, to compare e.g. JSON + zstd with a compact binary format.
+//!
+//! The codecs and levels used by the reports and benchmarks come from the `JSON_VS_BIN_COMPRESSION`
+//! environment variable if set, as a comma-separated list of `codec` or `codec:level` (e.g.
+//! `zstd:19,gzip:9,lz4`).
+use crate::formats::{DBusSignature, Error, Format};
+use serde::{de::DeserializeOwned, Serialize};
+use std::{
+    env,
+    fmt::{self, Display},
+    io::{Read, Write},
+    str::FromStr,
+};
+
+/// Environment variable overriding [`Compression::from_env`].
+pub const COMPRESSION_ENV: &str = "JSON_VS_BIN_COMPRESSION";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Codec {
+    Zstd,
+    Lz4,
+    Gzip,
+    Deflate,
+    Brotli,
+    Snappy,
+}
+
+impl Codec {
+    pub const ALL: [Codec; 6] = [
+        Codec::Zstd,
+        Codec::Lz4,
+        Codec::Gzip,
+        Codec::Deflate,
+        Codec::Brotli,
+        Codec::Snappy,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Codec::Zstd => "zstd",
+            Codec::Lz4 => "lz4",
+            Codec::Gzip => "gzip",
+            Codec::Deflate => "deflate",
+            Codec::Brotli => "brotli",
+            Codec::Snappy => "snappy",
+        }
+    }
+
+    /// The range of compression levels, `None` for codecs without levels.
+    pub fn levels(self) -> Option<(u32, u32)> {
+        match self {
+            Codec::Zstd => Some((1, 22)),
+            Codec::Gzip | Codec::Deflate => Some((0, 9)),
+            Codec::Brotli => Some((0, 11)),
+            Codec::Lz4 | Codec::Snappy => None,
+        }
+    }
+
+    /// The level used when none is given: the library default, except for brotli whose default
+    /// (11) is meant for static content and far too slow for messages.
+    pub fn default_level(self) -> Option<u32> {
+        match self {
+            Codec::Zstd => Some(zstd::DEFAULT_COMPRESSION_LEVEL as u32),
+            Codec::Gzip | Codec::Deflate => Some(6),
+            Codec::Brotli => Some(5),
+            Codec::Lz4 | Codec::Snappy => None,
+        }
+    }
+}
+
+/// A codec at a given level.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Compression {
+    pub codec: Codec,
+    /// `None` for codecs without levels.
+    pub level: Option<u32>,
+}
+
+impl Compression {
+    /// `codec` at its default level.
+    pub fn new(codec: Codec) -> Self {
+        Self {
+            codec,
+            level: codec.default_level(),
+        }
+    }
+
+    /// `codec` at `level`, if it's one of its levels.
+    pub fn with_level(codec: Codec, level: u32) -> Result<Self, Error> {
+        match codec.levels() {
+            Some((min, max)) if (min..=max).contains(&level) => Ok(Self {
+                codec,
+                level: Some(level),
+            }),
+            Some((min, max)) => {
+                Err(format!("{} levels go from {min} to {max}", codec.name()).into())
+            }
+            None => Err(format!("{} has no levels", codec.name()).into()),
+        }
+    }
+
+    /// Every codec at its default level.
+    pub fn defaults() -> Vec<Self> {
+        Codec::ALL.into_iter().map(Self::new).collect()
+    }
+
+    /// The compressions listed in `JSON_VS_BIN_COMPRESSION`, or [`Compression::defaults`].
+    pub fn from_env() -> Result<Vec<Self>, Error> {
+        match env::var(COMPRESSION_ENV) {
+            Ok(list) => list
+                .split(',')
+                .map(|compression| compression.trim().parse())
+                .collect::<Result<_, _>>()
+                .map_err(|e| format!("{COMPRESSION_ENV}: {e}").into()),
+            Err(_) => Ok(Self::defaults()),
+        }
+    }
+
+    pub fn compress(self, data: &[u8]) -> Result<Vec<u8>, Error> {
+        let level = self.level.unwrap_or_default();
+
+        Ok(match self.codec {
+            Codec::Zstd => zstd::bulk::compress(data, level as i32)?,
+            Codec::Lz4 => lz4_flex::compress_prepend_size(data),
+            Codec::Gzip => {
+                let mut encoder =
+                    flate2::write::GzEncoder::new(vec![], flate2::Compression::new(level));
+                encoder.write_all(data)?;
+                encoder.finish()?
+            }
+            Codec::Deflate => {
+                let mut encoder =
+                    flate2::write::DeflateEncoder::new(vec![], flate2::Compression::new(level));
+                encoder.write_all(data)?;
+                encoder.finish()?
+            }
+            Codec::Brotli => {
+                let mut compressed = vec![];
+                let params = brotli::enc::BrotliEncoderParams {
+                    quality: level as i32,
+                    ..Default::default()
+                };
+                brotli::BrotliCompress(&mut &data[..], &mut compressed, &params)?;
+                compressed
+            }
+            Codec::Snappy => snap::raw::Encoder::new().compress_vec(data)?,
+        })
+    }
+
+    pub fn decompress(self, data: &[u8]) -> Result<Vec<u8>, Error> {
+        let mut decompressed = vec![];
+        match self.codec {
+            Codec::Zstd => decompressed = zstd::decode_all(data)?,
+            Codec::Lz4 => decompressed = lz4_flex::decompress_size_prepended(data)?,
+            Codec::Gzip => {
+                flate2::read::GzDecoder::new(data).read_to_end(&mut decompressed)?;
+            }
+            Codec::Deflate => {
+                flate2::read::DeflateDecoder::new(data).read_to_end(&mut decompressed)?;
+            }
+            Codec::Brotli => {
+                brotli::BrotliDecompress(&mut &data[..], &mut decompressed)?;
+            }
+            Codec::Snappy => decompressed = snap::raw::Decoder::new().decompress_vec(data)?,
+        }
+
+        Ok(decompressed)
+    }
+
+    /// Encode `data` with `format` and compress the result.
+    pub fn try_encode<T: Serialize + DBusSignature + Clone>(
+        self,
+        format: Format,
+        data: &[T],
+    ) -> Result<Vec<u8>, Error> {
+        self.compress(&format.try_encode(data)?)
+    }
+
+    /// Decompress `bytes` and decode the result with `format`.
+    pub fn try_decode<T: DeserializeOwned + DBusSignature>(
+        self,
+        format: Format,
+        bytes: &[u8],
+    ) -> Result<Vec<T>, Error> {
+        format.try_decode(&self.decompress(bytes)?)
+    }
+}
+
+impl Display for Compression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.level {
+            Some(level) => write!(f, "{}-{level}", self.codec.name()),
+            None => f.write_str(self.codec.name()),
+        }
+    }
+}
+
+impl FromStr for Compression {
+    type Err = Error;
+
+    /// Parse `codec` or `codec:level`.
+    fn from_str(s: &str) -> Result<Self, Error> {
+        let (codec, level) = match s.split_once(':') {
+            Some((codec, level)) => (codec, Some(level)),
+            None => (s, None),
+        };
+        let codec = Codec::ALL
+            .into_iter()
+            .find(|c| c.name() == codec)
+            .ok_or_else(|| format!("unknown codec `{codec}`"))?;
+
+        match level {
+            Some(level) => Self::with_level(codec, level.parse()?),
+            None => Ok(Self::new(codec)),
+        }
+    }
+}
+
+/// Raw and compressed sizes of a payload in one format.
+#[derive(Debug)]
+pub struct CompressedSizes {
+    pub format: &'static str,
+    pub raw: Result<usize, Error>,
+    /// Compressed sizes, in the order of the compressions given to [`sizes`].
+    pub compressed: Vec<Result<usize, Error>>,
+}
+
+/// Encode `data` with every format and compress it with every one of `compressions`.
+pub fn sizes<T>(data: &[T], compressions: &[Compression]) -> Vec<CompressedSizes>
+where
+    T: Serialize + DBusSignature + Clone,
+{
+    Format::ALL
+        .iter()
+        .map(|format| {
+            let encoded = format.try_encode(data);
+            let compressed = compressions
+                .iter()
+                .map(|compression| match &encoded {
+                    Ok(encoded) => Ok(compression.compress(encoded)?.len()),
+                    Err(e) => Err(e.to_string().into()),
+                })
+                .collect();
+
+            CompressedSizes {
+                format: format.name(),
+                raw: encoded.map(|encoded| encoded.len()),
+                compressed,
+            }
+        })
+        .collect()
+}
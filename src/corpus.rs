@@ -0,0 +1,362 @@
+//! Typed models of the JSON documents commonly used to benchmark JSON libraries (`canada.json`,
+//! `citm_catalog.json` and `twitter.json`, as found in the `serde-rs/json-benchmark` and `simdjson`
+//! repositories), so real-world documents can go through every format.
+//!
+//! The files aren't distributed with this crate. They're loaded from the directory in the
+//! `JSON_VS_BIN_CORPUS_DIR` environment variable, `corpus` by default, and missing files are
+//! skipped.
+use crate::formats::{DBusSignature, Error};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    env, fs, io,
+    path::{Path, PathBuf},
+};
+use zvariant::{Signature, Type};
+
+/// Environment variable overriding the corpus directory.
+pub const CORPUS_DIR_ENV: &str = "JSON_VS_BIN_CORPUS_DIR";
+
+/// Corpus directory used when none is given.
+pub const DEFAULT_CORPUS_DIR: &str = "corpus";
+
+/// The corpus directory, from `JSON_VS_BIN_CORPUS_DIR` if set.
+pub fn dir() -> PathBuf {
+    env::var_os(CORPUS_DIR_ENV)
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(DEFAULT_CORPUS_DIR))
+}
+
+/// A corpus document, with the size of its original JSON file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Document<T> {
+    pub file_name: &'static str,
+    pub file_size: usize,
+    pub data: T,
+}
+
+/// The documents found in `dir`.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Corpus {
+    pub canada: Option<Document<Canada>>,
+    pub citm_catalog: Option<Document<CitmCatalog>>,
+    pub twitter: Option<Document<Twitter>>,
+}
+
+impl Corpus {
+    /// Load the documents in `dir`, skipping missing ones but failing on those that don't parse.
+    pub fn load(dir: &Path) -> Result<Self, Error> {
+        Ok(Self {
+            canada: load(dir, "canada.json")?,
+            citm_catalog: load(dir, "citm_catalog.json")?,
+            twitter: load(dir, "twitter.json")?,
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.canada.is_none() && self.citm_catalog.is_none() && self.twitter.is_none()
+    }
+}
+
+fn load<T: DeserializeOwned>(
+    dir: &Path,
+    file_name: &'static str,
+) -> Result<Option<Document<T>>, Error> {
+    let bytes = match fs::read(dir.join(file_name)) {
+        Ok(bytes) => bytes,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+    let data = serde_json::from_slice(&bytes).map_err(|e| format!("{file_name}: {e}"))?;
+
+    Ok(Some(Document {
+        file_name,
+        file_size: bytes.len(),
+        data,
+    }))
+}
+
+// canada.json: the Canadian border as GeoJSON, almost only floating point numbers.
+
+#[derive(Deserialize, Serialize, Type, PartialEq, Debug, Clone, Copy)]
+pub enum ObjType {
+    FeatureCollection,
+    Feature,
+    Polygon,
+}
+
+#[derive(Deserialize, Serialize, Type, PartialEq, Debug, Clone)]
+pub struct Canada {
+    #[serde(rename = "type")]
+    pub obj_type: ObjType,
+    pub features: Vec<Feature>,
+}
+
+#[derive(Deserialize, Serialize, Type, PartialEq, Debug, Clone)]
+pub struct Feature {
+    #[serde(rename = "type")]
+    pub obj_type: ObjType,
+    pub properties: HashMap<String, String>,
+    pub geometry: Geometry,
+}
+
+#[derive(Deserialize, Serialize, Type, PartialEq, Debug, Clone)]
+pub struct Geometry {
+    #[serde(rename = "type")]
+    pub obj_type: ObjType,
+    pub coordinates: Vec<Vec<(f64, f64)>>,
+}
+
+// citm_catalog.json: an event catalog, with many maps keyed by numeric IDs and lots of nulls.
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CitmCatalog {
+    pub area_names: HashMap<String, String>,
+    pub audience_sub_category_names: HashMap<String, String>,
+    pub block_names: HashMap<String, String>,
+    pub events: HashMap<String, CitmEvent>,
+    pub performances: Vec<Performance>,
+    pub seat_category_names: HashMap<String, String>,
+    pub sub_topic_names: HashMap<String, String>,
+    pub subject_names: HashMap<String, String>,
+    pub topic_names: HashMap<String, String>,
+    pub topic_sub_topics: HashMap<String, Vec<u32>>,
+    pub venue_names: HashMap<String, String>,
+}
+
+impl DBusSignature for CitmCatalog {
+    fn dbus_signature() -> Result<Signature, Error> {
+        Err("no null to represent `None` or `()`".into())
+    }
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CitmEvent {
+    pub description: (),
+    pub id: u32,
+    pub logo: Option<String>,
+    pub name: String,
+    pub sub_topic_ids: Vec<u32>,
+    pub subject_code: (),
+    pub subtitle: (),
+    pub topic_ids: Vec<u32>,
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Performance {
+    pub event_id: u32,
+    pub id: u32,
+    pub logo: Option<String>,
+    pub name: (),
+    pub prices: Vec<Price>,
+    pub seat_categories: Vec<SeatCategory>,
+    pub seat_map_image: (),
+    pub start: u64,
+    pub venue_code: String,
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Price {
+    pub amount: u32,
+    pub audience_sub_category_id: u32,
+    pub seat_category_id: u32,
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SeatCategory {
+    pub areas: Vec<Area>,
+    pub seat_category_id: u32,
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Area {
+    pub area_id: u32,
+    pub block_ids: [(); 0],
+}
+
+// twitter.json: a page of Twitter search API results, mostly (non-ASCII) strings.
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
+pub struct Twitter {
+    pub statuses: Vec<Status>,
+    pub search_metadata: SearchMetadata,
+}
+
+impl DBusSignature for Twitter {
+    fn dbus_signature() -> Result<Signature, Error> {
+        Err("no null to represent `None` or `()`".into())
+    }
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
+pub struct Status {
+    pub metadata: StatusMetadata,
+    pub created_at: String,
+    pub id: u64,
+    pub id_str: String,
+    pub text: String,
+    pub source: String,
+    pub truncated: bool,
+    pub in_reply_to_status_id: Option<u64>,
+    pub in_reply_to_status_id_str: Option<String>,
+    pub in_reply_to_user_id: Option<u32>,
+    pub in_reply_to_user_id_str: Option<String>,
+    pub in_reply_to_screen_name: Option<String>,
+    pub user: User,
+    pub geo: (),
+    pub coordinates: (),
+    pub place: (),
+    pub contributors: (),
+    pub retweeted_status: Option<Box<Status>>,
+    pub retweet_count: u32,
+    pub favorite_count: u32,
+    pub entities: StatusEntities,
+    pub favorited: bool,
+    pub retweeted: bool,
+    pub possibly_sensitive: Option<bool>,
+    pub lang: String,
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
+pub struct StatusMetadata {
+    pub result_type: String,
+    pub iso_language_code: String,
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
+pub struct User {
+    pub id: u32,
+    pub id_str: String,
+    pub name: String,
+    pub screen_name: String,
+    pub location: String,
+    pub description: String,
+    pub url: Option<String>,
+    pub entities: UserEntities,
+    pub protected: bool,
+    pub followers_count: u32,
+    pub friends_count: u32,
+    pub listed_count: u32,
+    pub created_at: String,
+    pub favourites_count: u32,
+    pub utc_offset: Option<i32>,
+    pub time_zone: Option<String>,
+    pub geo_enabled: bool,
+    pub verified: bool,
+    pub statuses_count: u32,
+    pub lang: String,
+    pub contributors_enabled: bool,
+    pub is_translator: bool,
+    pub is_translation_enabled: bool,
+    pub profile_background_color: String,
+    pub profile_background_image_url: String,
+    pub profile_background_image_url_https: String,
+    pub profile_background_tile: bool,
+    pub profile_image_url: String,
+    pub profile_image_url_https: String,
+    pub profile_banner_url: Option<String>,
+    pub profile_link_color: String,
+    pub profile_sidebar_border_color: String,
+    pub profile_sidebar_fill_color: String,
+    pub profile_text_color: String,
+    pub profile_use_background_image: bool,
+    pub default_profile: bool,
+    pub default_profile_image: bool,
+    pub following: bool,
+    pub follow_request_sent: bool,
+    pub notifications: bool,
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
+pub struct UserEntities {
+    pub url: Option<Urls>,
+    pub description: Urls,
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
+pub struct Urls {
+    pub urls: Vec<Url>,
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
+pub struct Url {
+    pub url: String,
+    pub expanded_url: String,
+    pub display_url: String,
+    pub indices: (u8, u8),
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
+pub struct StatusEntities {
+    pub hashtags: Vec<Hashtag>,
+    pub symbols: [(); 0],
+    pub urls: Vec<Url>,
+    pub user_mentions: Vec<UserMention>,
+    pub media: Option<Vec<Media>>,
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
+pub struct Hashtag {
+    pub text: String,
+    pub indices: (u8, u8),
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
+pub struct UserMention {
+    pub screen_name: String,
+    pub name: String,
+    pub id: u32,
+    pub id_str: String,
+    pub indices: (u8, u8),
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
+pub struct Media {
+    pub id: u64,
+    pub id_str: String,
+    pub indices: (u8, u8),
+    pub media_url: String,
+    pub media_url_https: String,
+    pub url: String,
+    pub display_url: String,
+    pub expanded_url: String,
+    #[serde(rename = "type")]
+    pub media_type: String,
+    pub sizes: Sizes,
+    pub source_status_id: Option<u64>,
+    pub source_status_id_str: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
+pub struct Sizes {
+    pub medium: Size,
+    pub small: Size,
+    pub thumb: Size,
+    pub large: Size,
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
+pub struct Size {
+    pub w: u16,
+    pub h: u16,
+    pub resize: String,
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
+pub struct SearchMetadata {
+    pub completed_in: f32,
+    pub max_id: u64,
+    pub max_id_str: String,
+    pub next_results: String,
+    pub query: String,
+    pub refresh_url: String,
+    pub count: u8,
+    pub since_id: u64,
+    pub since_id_str: String,
+}
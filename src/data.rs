@@ -229,29 +229,29 @@ impl<'a> BigData<'a> {
     pub fn new() -> BigData<'static> {
         // Create realistic HashMap with static keys.
         let mut properties = HashMap::new();
-        for i in 0..100 {
-            properties.insert(PROP_KEYS[i], i as u32);
+        for (i, key) in PROP_KEYS.iter().enumerate() {
+            properties.insert(*key, i as u32);
         }
 
         // Different HashMap instances with different key patterns to simulate variety.
         let mut metadata = HashMap::new();
-        for i in 0..100 {
-            metadata.insert(META_KEYS[i], (i * 2) as u32);
+        for (i, key) in META_KEYS.iter().enumerate() {
+            metadata.insert(*key, (i * 2) as u32);
         }
 
         let mut attributes = HashMap::new();
-        for i in 0..100 {
-            attributes.insert(ATTR_KEYS[i], (i * 3) as u32);
+        for (i, key) in ATTR_KEYS.iter().enumerate() {
+            attributes.insert(*key, (i * 3) as u32);
         }
 
         let mut headers = HashMap::new();
-        for i in 0..100 {
-            headers.insert(HEADER_KEYS[i], (i * 4) as u32);
+        for (i, key) in HEADER_KEYS.iter().enumerate() {
+            headers.insert(*key, (i * 4) as u32);
         }
 
         let mut tags = HashMap::new();
-        for i in 0..100 {
-            tags.insert(TAG_KEYS[i], (i * 5) as u32);
+        for (i, key) in TAG_KEYS.iter().enumerate() {
+            tags.insert(*key, (i * 5) as u32);
         }
 
         BigData {
@@ -306,8 +306,8 @@ impl<'a> SmallData<'a> {
     pub fn new() -> SmallData<'static> {
         // Create realistic HashMap with static keys.
         let mut config = HashMap::new();
-        for i in 0..100 {
-            config.insert(CFG_KEYS[i], i as u32);
+        for (i, key) in CFG_KEYS.iter().enumerate() {
+            config.insert(*key, i as u32);
         }
 
         SmallData {
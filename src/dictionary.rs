@@ -0,0 +1,184 @@
+//! zstd with a dictionary trained on sample messages, for services sending one small message per
+//! request.
+//!
+//! Generic compression does poorly on small messages: there's little in a single message for it
+//! to find repeats in. A dictionary trained on many messages of the same kind (for one format, as
+//! each format's bytes differ) gives it the common parts up front, so each message only pays for
+//! what makes it different.
+use crate::{
+    formats::{DBusSignature, Error, Format},
+    generator::{Generator, GeneratorConfig, DEFAULT_SEED},
+    measure,
+    vector_data::{SensorReading, SmallVectorData, Summary},
+};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use serde::Serialize;
+use std::{cell::RefCell, time::Duration};
+use zstd::bulk::{Compressor, Decompressor};
+
+/// Maximum size of a trained dictionary.
+pub const DICTIONARY_SIZE: usize = 16 * 1024;
+
+/// Number of messages a dictionary is trained on.
+pub const TRAINING_MESSAGES: usize = 1000;
+
+/// Number of messages, not seen in training, that sizes and times are averaged over.
+pub const TEST_MESSAGES: usize = 200;
+
+/// A zstd compressor and decompressor, with or without a dictionary, reused across messages as a
+/// service would.
+pub struct Zstd {
+    compressor: Compressor<'static>,
+    decompressor: Decompressor<'static>,
+}
+
+impl Zstd {
+    pub fn new(level: i32) -> Result<Self, Error> {
+        Self::with_dictionary(level, &[])
+    }
+
+    pub fn with_dictionary(level: i32, dictionary: &[u8]) -> Result<Self, Error> {
+        Ok(Self {
+            compressor: Compressor::with_dictionary(level, dictionary)?,
+            decompressor: Decompressor::with_dictionary(dictionary)?,
+        })
+    }
+
+    pub fn compress(&mut self, data: &[u8]) -> Result<Vec<u8>, Error> {
+        Ok(self.compressor.compress(data)?)
+    }
+
+    pub fn decompress(&mut self, data: &[u8]) -> Result<Vec<u8>, Error> {
+        // zstd frames written by `compress` record the size of their content.
+        let size = zstd::zstd_safe::get_frame_content_size(data)
+            .map_err(|_| "invalid zstd frame header")?
+            .ok_or("zstd frame without content size")?;
+
+        Ok(self.decompressor.decompress(data, size as usize)?)
+    }
+}
+
+/// Train a dictionary of at most [`DICTIONARY_SIZE`] bytes on `samples`.
+pub fn train<S: AsRef<[u8]>>(samples: &[S]) -> Result<Vec<u8>, Error> {
+    Ok(zstd::dict::from_samples(samples, DICTIONARY_SIZE)?)
+}
+
+/// Per-message sizes and times for one format, averaged over [`TEST_MESSAGES`] messages.
+#[derive(Debug)]
+pub struct DictionaryResult {
+    pub format: &'static str,
+    pub dictionary_size: usize,
+    pub raw: f64,
+    pub compressed: f64,
+    pub with_dictionary: f64,
+    /// Mean compress + decompress time without a dictionary.
+    pub time: Duration,
+    /// Mean compress + decompress time with the dictionary.
+    pub time_with_dictionary: Duration,
+}
+
+/// Train a dictionary per format on seeded
+/// [`OwnedSmallData`](crate::owned_data::OwnedSmallData) messages and measure it on others.
+pub fn small_data_report() -> Result<Vec<Result<DictionaryResult, Error>>, Error> {
+    let mut generator = Generator::new(GeneratorConfig::from_env()?);
+    let payloads = generator.generate(0, TRAINING_MESSAGES + TEST_MESSAGES);
+
+    Ok(report(payloads.owned_small_data()))
+}
+
+/// Train a dictionary per format on [`SmallVectorData`] messages and measure it on others.
+pub fn small_vector_report() -> Vec<Result<DictionaryResult, Error>> {
+    let mut rng = ChaCha8Rng::seed_from_u64(DEFAULT_SEED);
+    let messages = (0..TRAINING_MESSAGES + TEST_MESSAGES)
+        .map(|_| small_vector_message(&mut rng))
+        .collect::<Vec<_>>();
+
+    report(&messages)
+}
+
+// `messages` starts with the training messages, then the test messages.
+fn report<T>(messages: &[T]) -> Vec<Result<DictionaryResult, Error>>
+where
+    T: Serialize + DBusSignature + Clone,
+{
+    Format::ALL
+        .iter()
+        .map(|format| {
+            let encoded = messages
+                .iter()
+                .map(|message| format.try_encode(std::slice::from_ref(message)))
+                .collect::<Result<Vec<_>, _>>()?;
+            let (training, test) = encoded.split_at(TRAINING_MESSAGES);
+            let dictionary = train(training)?;
+            let plain = RefCell::new(Zstd::new(zstd::DEFAULT_COMPRESSION_LEVEL)?);
+            let trained = RefCell::new(Zstd::with_dictionary(
+                zstd::DEFAULT_COMPRESSION_LEVEL,
+                &dictionary,
+            )?);
+
+            // Mean compressed size, checking every message decompresses back.
+            let size = |zstd: &RefCell<Zstd>| -> Result<f64, Error> {
+                let mut total = 0;
+                for message in test {
+                    let compressed = zstd.borrow_mut().compress(message)?;
+                    if zstd.borrow_mut().decompress(&compressed)? != *message {
+                        return Err("decompressed message differs from the original".into());
+                    }
+                    total += compressed.len();
+                }
+
+                Ok(total as f64 / test.len() as f64)
+            };
+            let time = |zstd: &RefCell<Zstd>| {
+                let time = measure::time(|| {
+                    for message in test {
+                        let mut zstd = zstd.borrow_mut();
+                        let compressed = zstd.compress(message).unwrap();
+                        zstd.decompress(&compressed).unwrap();
+                    }
+                });
+                time / test.len() as u32
+            };
+
+            Ok(DictionaryResult {
+                format: format.name(),
+                dictionary_size: dictionary.len(),
+                raw: test.iter().map(Vec::len).sum::<usize>() as f64 / test.len() as f64,
+                compressed: size(&plain)?,
+                with_dictionary: size(&trained)?,
+                time: time(&plain),
+                time_with_dictionary: time(&trained),
+            })
+        })
+        .collect()
+}
+
+// A `SmallVectorData` with random readings, as `SmallVectorData::new` always makes the same one.
+fn small_vector_message(rng: &mut ChaCha8Rng) -> SmallVectorData {
+    let base_timestamp = 1_700_000_000_000_000 + rng.random_range(0..1_000_000_000_000u64);
+    let readings = (0..100)
+        .map(|i| SensorReading {
+            timestamp: base_timestamp + i * 1000,
+            sensor_id: rng.random_range(0..10),
+            temperature: rng.random_range(15.0..25.0),
+            humidity: rng.random_range(30.0..70.0),
+            pressure: rng.random_range(1000.0..1025.0),
+            battery: rng.random_range(0..=100),
+            status: rng.random_range(0..4),
+        })
+        .collect::<Vec<_>>();
+    let temperatures = readings.iter().map(|r| r.temperature);
+
+    SmallVectorData {
+        summary: Summary {
+            count: readings.len() as u32,
+            min_temp: temperatures.clone().fold(f32::INFINITY, f32::min),
+            max_temp: temperatures.clone().fold(f32::NEG_INFINITY, f32::max),
+            avg_temp: temperatures.sum::<f32>() / readings.len() as f32,
+            min_timestamp: base_timestamp,
+            max_timestamp: base_timestamp + 99_000,
+        },
+        readings,
+    }
+}
@@ -0,0 +1,370 @@
+//! An event stream built from tagged enums and optional fields, where formats differ the most:
+//! the self-describing formats (JSON, BSON) tag enum variants with their names, the compact binary
+//! ones can't decode serde's internally tagged, adjacently tagged or untagged enums, and D-Bus has
+//! no null and no way to describe enums whose variants carry different data.
+//!
+//! Strings are owned since the point here is representability, not borrowing.
+use crate::formats::{DBusSignature, Error, Format};
+use serde::{Deserialize, Serialize};
+use std::panic::{self, AssertUnwindSafe};
+use zvariant::{Signature, Type};
+
+/// Unit-only enum, which D-Bus can represent (as a `u32`).
+#[derive(Deserialize, Serialize, Type, PartialEq, Debug, Clone, Copy)]
+pub enum Level {
+    Trace,
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
+pub enum Source {
+    Internal,
+    Service(String),
+    Device { id: u32, firmware: Option<String> },
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
+pub enum ErrorKind {
+    Timeout(u32),
+    Http { status: u16, body: Option<String> },
+    Unknown,
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
+pub struct Purchase {
+    pub sku: String,
+    pub quantity: u32,
+    pub price_cents: u64,
+    pub coupon: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
+pub enum Payload {
+    Heartbeat,
+    Click(u32, u32),
+    PageView {
+        url: String,
+        referrer: Option<String>,
+        duration_ms: Option<u32>,
+    },
+    Purchase(Purchase),
+    Error {
+        kind: ErrorKind,
+        retryable: bool,
+    },
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
+pub struct Event {
+    pub id: u64,
+    pub timestamp: u64,
+    pub level: Level,
+    pub source: Source,
+    pub payload: Payload,
+    pub session_id: Option<u64>,
+    pub user_id: Option<u32>,
+    pub location: Option<(f64, f64)>,
+    pub tags: Vec<(String, Option<String>)>,
+    pub parent: Option<Box<Event>>,
+}
+
+impl Event {
+    /// `count` events cycling through every variant, with optional fields alternately set.
+    pub fn stream(count: usize) -> Vec<Event> {
+        let base_timestamp = 1_700_000_000_000_000u64;
+
+        (0..count)
+            .map(|i| {
+                let event = Event {
+                    id: i as u64,
+                    timestamp: base_timestamp + i as u64 * 1000,
+                    level: [
+                        Level::Trace,
+                        Level::Debug,
+                        Level::Info,
+                        Level::Warn,
+                        Level::Error,
+                    ][i % 5],
+                    source: match i % 3 {
+                        0 => Source::Internal,
+                        1 => Source::Service(format!("service-{}", i % 7)),
+                        _ => Source::Device {
+                            id: i as u32,
+                            firmware: i.is_multiple_of(2).then(|| "1.2.3".to_string()),
+                        },
+                    },
+                    payload: Self::payload(i),
+                    session_id: i.is_multiple_of(2).then_some(987654321 + i as u64),
+                    user_id: i.is_multiple_of(3).then_some(1000 + i as u32),
+                    location: i.is_multiple_of(4).then_some((52.52, 13.405)),
+                    tags: vec![
+                        ("env".to_string(), Some("prod".to_string())),
+                        ("canary".to_string(), None),
+                    ],
+                    parent: None,
+                };
+
+                // Every tenth event nests its cause.
+                if i % 10 == 9 {
+                    Event {
+                        parent: Some(Box::new(Event {
+                            id: event.id + 1_000_000,
+                            payload: Payload::Heartbeat,
+                            ..event.clone()
+                        })),
+                        ..event
+                    }
+                } else {
+                    event
+                }
+            })
+            .collect()
+    }
+
+    fn payload(i: usize) -> Payload {
+        match i % 5 {
+            0 => Payload::Heartbeat,
+            1 => Payload::Click((i * 7 % 1920) as u32, (i * 13 % 1080) as u32),
+            2 => Payload::PageView {
+                url: format!("https://example.com/items/{i}"),
+                referrer: (i % 4 == 2).then(|| "https://example.com/".to_string()),
+                duration_ms: (!i.is_multiple_of(3)).then_some(i as u32 * 10),
+            },
+            3 => Payload::Purchase(Purchase {
+                sku: format!("SKU-{:05}", i % 1000),
+                quantity: (i % 5 + 1) as u32,
+                price_cents: 1999 + i as u64,
+                coupon: (i % 6 == 3).then(|| "SPRING".to_string()),
+            }),
+            _ => Payload::Error {
+                kind: match i % 3 {
+                    0 => ErrorKind::Timeout(30_000),
+                    1 => ErrorKind::Http {
+                        status: 503,
+                        body: i
+                            .is_multiple_of(2)
+                            .then(|| "Service Unavailable".to_string()),
+                    },
+                    _ => ErrorKind::Unknown,
+                },
+                retryable: i.is_multiple_of(2),
+            },
+        }
+    }
+}
+
+impl DBusSignature for Event {
+    fn dbus_signature() -> Result<Signature, Error> {
+        Err("no null to represent `None` and enum variants must all carry the same data".into())
+    }
+}
+
+/// Enum variants carrying different data (newtype, tuple and struct variants).
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
+pub enum Shape {
+    Point,
+    Circle(f64),
+    Rectangle(f64, f64),
+    Polygon { sides: u8, length: f64 },
+}
+
+impl DBusSignature for Shape {
+    fn dbus_signature() -> Result<Signature, Error> {
+        Err("enum variants must all carry the same data".into())
+    }
+}
+
+/// Present and absent optional fields.
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
+pub struct Optional {
+    pub id: u32,
+    pub name: Option<String>,
+    pub score: Option<f64>,
+}
+
+impl DBusSignature for Optional {
+    fn dbus_signature() -> Result<Signature, Error> {
+        Err("no null to represent `None`".into())
+    }
+}
+
+/// Optional fields left out of the output when `None`, common in JSON APIs.
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
+pub struct Skipped {
+    pub id: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub count: u32,
+}
+
+impl DBusSignature for Skipped {
+    fn dbus_signature() -> Result<Signature, Error> {
+        Err("no null to represent `None`".into())
+    }
+}
+
+/// Internally tagged enum (`{"type": "Circle", "radius": 1.0}`).
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
+#[serde(tag = "type")]
+pub enum Internal {
+    Point,
+    Circle { radius: f64 },
+    Rectangle { width: f64, height: f64 },
+}
+
+impl DBusSignature for Internal {
+    fn dbus_signature() -> Result<Signature, Error> {
+        Err("enum variants must all carry the same data".into())
+    }
+}
+
+/// Adjacently tagged enum (`{"t": "Circle", "c": 1.0}`).
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
+#[serde(tag = "t", content = "c")]
+pub enum Adjacent {
+    Point,
+    Circle(f64),
+    Rectangle(f64, f64),
+}
+
+impl DBusSignature for Adjacent {
+    fn dbus_signature() -> Result<Signature, Error> {
+        Err("enum variants must all carry the same data".into())
+    }
+}
+
+/// Untagged enum, told apart by its content on decode.
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
+#[serde(untagged)]
+pub enum Untagged {
+    Number(u64),
+    Text(String),
+    Pair(u64, String),
+}
+
+impl DBusSignature for Untagged {
+    fn dbus_signature() -> Result<Signature, Error> {
+        Err("enum variants must all carry the same data".into())
+    }
+}
+
+/// How a format handles one case of the report.
+pub enum Outcome {
+    /// The data round-trips unchanged.
+    Ok,
+    /// The format has no way to describe the type (D-Bus), so it isn't tried.
+    Unsupported(Error),
+    /// Encoding or decoding fails, or the data comes back different.
+    Fails(Error),
+}
+
+/// Which formats can round-trip each enum and `Option` feature, case by case.
+///
+/// Each case comes with one outcome per format, in the order of [`Format::ALL`].
+pub fn report() -> Vec<(&'static str, Vec<Outcome>)> {
+    let levels = [
+        Level::Trace,
+        Level::Debug,
+        Level::Info,
+        Level::Warn,
+        Level::Error,
+    ];
+    let shapes = [
+        Shape::Point,
+        Shape::Circle(1.5),
+        Shape::Rectangle(2.0, 3.0),
+        Shape::Polygon {
+            sides: 6,
+            length: 1.0,
+        },
+    ];
+    let optional = [
+        Optional {
+            id: 1,
+            name: Some("one".to_string()),
+            score: Some(0.5),
+        },
+        Optional {
+            id: 2,
+            name: None,
+            score: None,
+        },
+    ];
+    let skipped = [
+        Skipped {
+            id: 1,
+            name: Some("one".to_string()),
+            count: 10,
+        },
+        Skipped {
+            id: 2,
+            name: None,
+            count: 20,
+        },
+    ];
+    let internal = [
+        Internal::Point,
+        Internal::Circle { radius: 1.5 },
+        Internal::Rectangle {
+            width: 2.0,
+            height: 3.0,
+        },
+    ];
+    let adjacent = [
+        Adjacent::Point,
+        Adjacent::Circle(1.5),
+        Adjacent::Rectangle(2.0, 3.0),
+    ];
+    let untagged = [
+        Untagged::Number(42),
+        Untagged::Text("forty-two".to_string()),
+        Untagged::Pair(42, "forty-two".to_string()),
+    ];
+    let events = Event::stream(100);
+
+    vec![
+        ("Unit variants", roundtrip(&levels)),
+        ("Data variants", roundtrip(&shapes)),
+        ("Option fields", roundtrip(&optional)),
+        ("Skipped `None`", roundtrip(&skipped)),
+        ("Internally tagged", roundtrip(&internal)),
+        ("Adjacently tagged", roundtrip(&adjacent)),
+        ("Untagged", roundtrip(&untagged)),
+        ("Event stream", roundtrip(&events)),
+    ]
+}
+
+// Some formats panic rather than return an error on data they can't handle (Bitcode on fields
+// skipped during serialization), so panics are caught and reported as failures too. The panic
+// hook is left alone, so their messages still show on stderr.
+fn roundtrip<T>(data: &[T]) -> Vec<Outcome>
+where
+    T: Serialize + for<'de> Deserialize<'de> + DBusSignature + Clone + PartialEq,
+{
+    Format::ALL
+        .iter()
+        .map(|format| {
+            if *format == Format::DBus {
+                if let Err(e) = T::dbus_signature() {
+                    return Outcome::Unsupported(e);
+                }
+            }
+
+            match panic::catch_unwind(AssertUnwindSafe(|| format.try_roundtrip(data))) {
+                Ok(Ok(())) => Outcome::Ok,
+                Ok(Err(e)) => Outcome::Fails(e),
+                Err(panic) => {
+                    let message = panic
+                        .downcast_ref::<&str>()
+                        .map(|s| s.to_string())
+                        .or_else(|| panic.downcast_ref::<String>().cloned())
+                        .unwrap_or_default();
+                    Outcome::Fails(format!("panicked: {message}").into())
+                }
+            }
+        })
+        .collect()
+}
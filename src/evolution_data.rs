@@ -0,0 +1,412 @@
+//! Schema evolution: second versions of [`OwnedSmallData`] and [`OwnedLogEvent`], each with one
+//! change, to see which formats let old readers read new data and new readers read old data.
+//!
+//! The self-describing formats (JSON, BSON) match fields by name, so they skip unknown fields and
+//! fill in defaults; serde aliases let them read renamed fields too. The other formats only know
+//! the order and types of the fields, so a change either fails to decode or, worse, decodes to
+//! different values, as when two fields of the same type swap places.
+use crate::{
+    formats::{DBusSignature, Error, Format},
+    owned_data::{OwnedBigVectorData, OwnedLogEvent, OwnedSmallData},
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{collections::HashMap, convert::identity, iter};
+use zvariant::{Signature, Type};
+
+/// Number of instances in the payloads, as several make the formats that ignore trailing input read
+/// past the end of each instance but the last.
+pub const INSTANCES: usize = 10;
+
+/// [`OwnedSmallData`] with an optional field added at the end.
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
+pub struct AddedSmallData {
+    pub id: u64,
+    pub status: u8,
+    pub enabled: bool,
+    pub name: String,
+    pub retries: u8,
+    pub description: String,
+    pub config: HashMap<String, u32>,
+    #[serde(default)]
+    pub owner: Option<String>,
+}
+
+impl DBusSignature for AddedSmallData {
+    fn dbus_signature() -> Result<Signature, Error> {
+        Err("no null to represent `None`".into())
+    }
+}
+
+/// [`OwnedSmallData`] without `description`.
+#[derive(Deserialize, Serialize, Type, PartialEq, Debug, Clone)]
+pub struct RemovedSmallData {
+    pub id: u64,
+    pub status: u8,
+    pub enabled: bool,
+    pub name: String,
+    pub retries: u8,
+    pub config: HashMap<String, u32>,
+}
+
+/// [`OwnedSmallData`] with `name` and `description` swapped.
+#[derive(Deserialize, Serialize, Type, PartialEq, Debug, Clone)]
+pub struct ReorderedSmallData {
+    pub id: u64,
+    pub status: u8,
+    pub enabled: bool,
+    pub description: String,
+    pub retries: u8,
+    pub name: String,
+    pub config: HashMap<String, u32>,
+}
+
+/// [`OwnedSmallData`] with `retries` widened from `u8` to `u32`.
+#[derive(Deserialize, Serialize, Type, PartialEq, Debug, Clone)]
+pub struct WidenedSmallData {
+    pub id: u64,
+    pub status: u8,
+    pub enabled: bool,
+    pub name: String,
+    pub retries: u32,
+    pub description: String,
+    pub config: HashMap<String, u32>,
+}
+
+/// [`OwnedSmallData`] with `name` renamed to `title`, still accepting `name`.
+#[derive(Deserialize, Serialize, Type, PartialEq, Debug, Clone)]
+pub struct RenamedSmallData {
+    pub id: u64,
+    pub status: u8,
+    pub enabled: bool,
+    #[serde(alias = "name")]
+    pub title: String,
+    pub retries: u8,
+    pub description: String,
+    pub config: HashMap<String, u32>,
+}
+
+/// [`OwnedLogEvent`] with an optional field added at the end.
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
+pub struct AddedLogEvent {
+    pub timestamp: u64,
+    pub level: u8,
+    pub component: String,
+    pub message: String,
+    pub trace_id: u64,
+    pub span_id: u64,
+    pub user_id: u32,
+    #[serde(default)]
+    pub host: Option<String>,
+}
+
+impl DBusSignature for AddedLogEvent {
+    fn dbus_signature() -> Result<Signature, Error> {
+        Err("no null to represent `None`".into())
+    }
+}
+
+/// [`OwnedLogEvent`] without `span_id`.
+#[derive(Deserialize, Serialize, Type, PartialEq, Debug, Clone)]
+pub struct RemovedLogEvent {
+    pub timestamp: u64,
+    pub level: u8,
+    pub component: String,
+    pub message: String,
+    pub trace_id: u64,
+    pub user_id: u32,
+}
+
+/// [`OwnedLogEvent`] with `level` moved to the end.
+#[derive(Deserialize, Serialize, Type, PartialEq, Debug, Clone)]
+pub struct ReorderedLogEvent {
+    pub timestamp: u64,
+    pub component: String,
+    pub message: String,
+    pub trace_id: u64,
+    pub span_id: u64,
+    pub user_id: u32,
+    pub level: u8,
+}
+
+/// [`OwnedLogEvent`] with `user_id` widened from `u32` to `u64`.
+#[derive(Deserialize, Serialize, Type, PartialEq, Debug, Clone)]
+pub struct WidenedLogEvent {
+    pub timestamp: u64,
+    pub level: u8,
+    pub component: String,
+    pub message: String,
+    pub trace_id: u64,
+    pub span_id: u64,
+    pub user_id: u64,
+}
+
+/// [`OwnedLogEvent`] with `component` renamed to `module`, still accepting `component`.
+#[derive(Deserialize, Serialize, Type, PartialEq, Debug, Clone)]
+pub struct RenamedLogEvent {
+    pub timestamp: u64,
+    pub level: u8,
+    #[serde(alias = "component")]
+    pub module: String,
+    pub message: String,
+    pub trace_id: u64,
+    pub span_id: u64,
+    pub user_id: u32,
+}
+
+/// Whether a reader decodes what a writer of the other version encoded.
+#[derive(Debug)]
+pub enum Compatibility {
+    /// Decoded to the values the reader should see.
+    Reads,
+    /// Decoded without error, but to different values.
+    Misreads,
+    /// Failed to encode or decode.
+    Fails(Error),
+}
+
+/// The results of one change, with one result per format in the order of [`Format::ALL`].
+#[derive(Debug)]
+pub struct Case {
+    pub change: &'static str,
+    /// Size of the payload encoded by the new version.
+    pub sizes: Vec<Result<usize, Error>>,
+    pub old_reads_new: Vec<Compatibility>,
+    pub new_reads_old: Vec<Compatibility>,
+}
+
+/// The changes to one payload.
+#[derive(Debug)]
+pub struct Evolution {
+    pub payload: &'static str,
+    /// Size of the payload encoded by the original version.
+    pub sizes: Vec<Result<usize, Error>>,
+    pub cases: Vec<Case>,
+}
+
+/// Every change to [`INSTANCES`] [`OwnedSmallData`] and [`OwnedLogEvent`]s.
+pub fn report() -> Vec<Evolution> {
+    let small = iter::repeat_with(OwnedSmallData::new)
+        .take(INSTANCES)
+        .collect::<Vec<_>>();
+    let logs = OwnedBigVectorData::new()
+        .logs
+        .into_iter()
+        .take(INSTANCES)
+        .collect::<Vec<_>>();
+
+    vec![
+        Evolution {
+            payload: "SmallData",
+            sizes: sizes(&small),
+            cases: vec![
+                case::<_, AddedSmallData>(
+                    "Added optional field",
+                    &small,
+                    |data| AddedSmallData {
+                        owner: Some("ops".to_string()),
+                        ..data
+                    },
+                    |data| Some(data.into()),
+                ),
+                case::<_, RemovedSmallData>("Removed field", &small, identity, |_| None),
+                case::<_, ReorderedSmallData>("Reordered fields", &small, identity, |data| {
+                    Some(data.into())
+                }),
+                case::<_, WidenedSmallData>("Widened integer", &small, identity, |data| {
+                    Some(OwnedSmallData {
+                        id: data.id,
+                        status: data.status,
+                        enabled: data.enabled,
+                        name: data.name.clone(),
+                        retries: data.retries.try_into().ok()?,
+                        description: data.description.clone(),
+                        config: data.config.clone(),
+                    })
+                }),
+                case::<_, RenamedSmallData>("Renamed with alias", &small, identity, |data| {
+                    Some(data.into())
+                }),
+            ],
+        },
+        Evolution {
+            payload: "LogEvent",
+            sizes: sizes(&logs),
+            cases: vec![
+                case::<_, AddedLogEvent>(
+                    "Added optional field",
+                    &logs,
+                    |event| AddedLogEvent {
+                        host: Some("web-1".to_string()),
+                        ..event
+                    },
+                    |event| Some(event.into()),
+                ),
+                case::<_, RemovedLogEvent>("Removed field", &logs, identity, |_| None),
+                case::<_, ReorderedLogEvent>("Reordered fields", &logs, identity, |event| {
+                    Some(event.into())
+                }),
+                case::<_, WidenedLogEvent>("Widened integer", &logs, identity, |event| {
+                    Some(OwnedLogEvent {
+                        timestamp: event.timestamp,
+                        level: event.level,
+                        component: event.component.clone(),
+                        message: event.message.clone(),
+                        trace_id: event.trace_id,
+                        span_id: event.span_id,
+                        user_id: event.user_id.try_into().ok()?,
+                    })
+                }),
+                case::<_, RenamedLogEvent>("Renamed with alias", &logs, identity, |event| {
+                    Some(event.into())
+                }),
+            ],
+        },
+    ]
+}
+
+// Write `old` with the original version and read it with the new one, and the other way round.
+//
+// A new reader reading old data should see what `N::from` makes of it, and the new version writes
+// that after `set` fills in what the old version doesn't have. `downgrade` is what the original
+// version should read back from the new data (`None` if there's no right answer, as for a removed
+// field or a widened value that doesn't fit the original type).
+fn case<O, N>(
+    change: &'static str,
+    old: &[O],
+    set: impl Fn(N) -> N,
+    downgrade: impl Fn(&N) -> Option<O>,
+) -> Case
+where
+    O: Serialize + DeserializeOwned + DBusSignature + Clone + PartialEq,
+    N: Serialize + DeserializeOwned + DBusSignature + Clone + PartialEq + for<'a> From<&'a O>,
+{
+    let new_from_old = old.iter().map(N::from).collect::<Vec<_>>();
+    let new = new_from_old.iter().cloned().map(set).collect::<Vec<_>>();
+    let old_from_new = new.iter().map(downgrade).collect::<Option<Vec<_>>>();
+
+    Case {
+        change,
+        sizes: sizes(&new),
+        old_reads_new: Format::ALL
+            .iter()
+            .map(|format| read(*format, &new, old_from_new.as_deref()))
+            .collect(),
+        new_reads_old: Format::ALL
+            .iter()
+            .map(|format| read(*format, old, Some(&new_from_old)))
+            .collect(),
+    }
+}
+
+// Encode `written` with `format` and decode it as `R`, expecting `expected`.
+fn read<W, R>(format: Format, written: &[W], expected: Option<&[R]>) -> Compatibility
+where
+    W: Serialize + DBusSignature + Clone,
+    R: DeserializeOwned + DBusSignature + PartialEq,
+{
+    let decoded = format
+        .try_encode(written)
+        .and_then(|encoded| format.try_decode::<R>(&encoded));
+
+    match decoded {
+        Ok(decoded) if Some(&decoded[..]) == expected => Compatibility::Reads,
+        Ok(_) => Compatibility::Misreads,
+        Err(e) => Compatibility::Fails(e),
+    }
+}
+
+fn sizes<T: Serialize + DBusSignature + Clone>(data: &[T]) -> Vec<Result<usize, Error>> {
+    Format::ALL
+        .iter()
+        .map(|format| format.try_encode(data).map(|encoded| encoded.len()))
+        .collect()
+}
+
+// Conversions between the versions: what each should read of data written by the other. They copy
+// the fields both versions have, listed once, widening integers where the new version's are wider.
+// `<=>` converts both ways and `=>` only from the original, as for removed fields or narrowing
+// integers, which can't always be done. Renamed fields are listed as `original: new`, and the
+// fields only the new version has get the values listed as added.
+macro_rules! convert {
+    (
+        $old:ident => $new:ident { $($field:ident),* }
+        $(renamed { $($old_name:ident: $new_name:ident),* })?
+        $(added { $($added:ident: $value:expr),* })?
+    ) => {
+        impl From<&$old> for $new {
+            fn from(old: &$old) -> Self {
+                Self {
+                    $($field: copy(&old.$field),)*
+                    $($($new_name: copy(&old.$old_name),)*)?
+                    $($($added: $value,)*)?
+                }
+            }
+        }
+    };
+    (
+        $old:ident <=> $new:ident { $($field:ident),* }
+        $(renamed { $($old_name:ident: $new_name:ident),* })?
+        $(added { $($added:ident: $value:expr),* })?
+    ) => {
+        convert!(
+            $old => $new { $($field),* }
+            $(renamed { $($old_name: $new_name),* })?
+            $(added { $($added: $value),* })?
+        );
+
+        impl From<&$new> for $old {
+            fn from(new: &$new) -> Self {
+                Self {
+                    $($field: copy(&new.$field),)*
+                    $($($old_name: copy(&new.$new_name),)*)?
+                }
+            }
+        }
+    };
+}
+
+fn copy<T: Clone + Into<U>, U>(value: &T) -> U {
+    value.clone().into()
+}
+
+convert!(
+    OwnedSmallData <=> AddedSmallData { id, status, enabled, name, retries, description, config }
+    added { owner: None }
+);
+convert!(OwnedSmallData => RemovedSmallData { id, status, enabled, name, retries, config });
+convert!(
+    OwnedSmallData <=> ReorderedSmallData {
+        id, status, enabled, name, retries, description, config
+    }
+);
+convert!(
+    OwnedSmallData => WidenedSmallData { id, status, enabled, name, retries, description, config }
+);
+convert!(
+    OwnedSmallData <=> RenamedSmallData { id, status, enabled, retries, description, config }
+    renamed { name: title }
+);
+
+convert!(
+    OwnedLogEvent <=> AddedLogEvent {
+        timestamp, level, component, message, trace_id, span_id, user_id
+    }
+    added { host: None }
+);
+convert!(
+    OwnedLogEvent => RemovedLogEvent { timestamp, level, component, message, trace_id, user_id }
+);
+convert!(
+    OwnedLogEvent <=> ReorderedLogEvent {
+        timestamp, level, component, message, trace_id, span_id, user_id
+    }
+);
+convert!(
+    OwnedLogEvent => WidenedLogEvent {
+        timestamp, level, component, message, trace_id, span_id, user_id
+    }
+);
+convert!(
+    OwnedLogEvent <=> RenamedLogEvent { timestamp, level, message, trace_id, span_id, user_id }
+    renamed { component: module }
+);
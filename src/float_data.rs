@@ -0,0 +1,206 @@
+//! A numeric-heavy payload (time series and a matrix) with values using the full precision of
+//! their type, unlike the smooth sinusoids of [`crate::vector_data`], and a checker for bit-exact
+//! float round trips, including the special values (NaN, infinities, -0.0, subnormals) text formats
+//! have trouble with.
+use crate::{
+    formats::{Error, Format},
+    generator::DEFAULT_SEED,
+};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+use zvariant::Type;
+
+/// Special and edge-case `f64` values, with their names.
+pub const SPECIAL_F64: [(&str, f64); 12] = [
+    ("NaN", f64::NAN),
+    ("-NaN", -f64::NAN),
+    // A NaN with a non-default payload, which some platforms use to carry information.
+    ("NaN payload", f64::from_bits(0x7ff8_0000_dead_beef)),
+    ("+Inf", f64::INFINITY),
+    ("-Inf", f64::NEG_INFINITY),
+    ("-0.0", -0.0),
+    ("Min subnormal", f64::from_bits(1)),
+    ("Min normal", f64::MIN_POSITIVE),
+    ("Max", f64::MAX),
+    ("Epsilon", f64::EPSILON),
+    ("0.1", 0.1),
+    ("1/3", 1.0 / 3.0),
+];
+
+/// Special and edge-case `f32` values, with their names.
+pub const SPECIAL_F32: [(&str, f32); 12] = [
+    ("NaN", f32::NAN),
+    ("-NaN", -f32::NAN),
+    ("NaN payload", f32::from_bits(0x7fc0_beef)),
+    ("+Inf", f32::INFINITY),
+    ("-Inf", f32::NEG_INFINITY),
+    ("-0.0", -0.0),
+    ("Min subnormal", f32::from_bits(1)),
+    ("Min normal", f32::MIN_POSITIVE),
+    ("Max", f32::MAX),
+    ("Epsilon", f32::EPSILON),
+    ("0.1", 0.1),
+    ("1/3", 1.0 / 3.0),
+];
+
+#[derive(Deserialize, Serialize, Type, PartialEq, Debug, Clone)]
+pub struct NumericData {
+    pub start: u64,
+    pub interval_ms: u32,
+    /// A random walk, so every value uses all 53 bits of mantissa.
+    pub series: Vec<f64>,
+    pub series_f32: Vec<f32>,
+    /// Row-major, with magnitudes from 1e-30 to 1e30.
+    pub matrix: Vec<Vec<f64>>,
+}
+
+impl NumericData {
+    /// A payload with `len` values per series and a `len`×`len/10` matrix, all finite and normal.
+    pub fn new(len: usize) -> Self {
+        let mut rng = ChaCha8Rng::seed_from_u64(DEFAULT_SEED);
+        let mut value = 100.0;
+        let series = (0..len)
+            .map(|_| {
+                value += rng.random::<f64>() - 0.5;
+                value
+            })
+            .collect();
+        let series_f32 = (0..len)
+            .map(|_| rng.random::<f32>() * 1000.0 - 500.0)
+            .collect();
+        let matrix = (0..len / 10)
+            .map(|_| {
+                (0..len)
+                    .map(|_| rng.random::<f64>() * 10f64.powi(rng.random_range(-30..30)))
+                    .collect()
+            })
+            .collect();
+
+        Self {
+            start: 1_700_000_000_000,
+            interval_ms: 100,
+            series,
+            series_f32,
+            matrix,
+        }
+    }
+
+    /// [`NumericData::new`] with the special values spread through the series and the first row of
+    /// the matrix, if there is one. Series shorter than [`SPECIAL_F64`] only keep some of them.
+    pub fn with_special_values(len: usize) -> Self {
+        let mut data = Self::new(len);
+        if len == 0 {
+            return data;
+        }
+
+        for (i, (_, value)) in SPECIAL_F64.iter().enumerate() {
+            let index = i * len / SPECIAL_F64.len();
+            data.series[index] = *value;
+            if let Some(row) = data.matrix.first_mut() {
+                row[index] = *value;
+            }
+        }
+        for (i, (_, value)) in SPECIAL_F32.iter().enumerate() {
+            data.series_f32[i * len / SPECIAL_F32.len()] = *value;
+        }
+
+        data
+    }
+
+    // Bits of every float, in order.
+    fn bits(&self) -> Vec<u64> {
+        self.series
+            .iter()
+            .chain(self.matrix.iter().flatten())
+            .map(|value| value.to_bits())
+            .chain(self.series_f32.iter().map(|value| value.to_bits() as u64))
+            .collect()
+    }
+}
+
+/// Outcome of a bit-exact round trip.
+#[derive(Debug)]
+pub enum RoundTrip {
+    Exact,
+    /// Decoded, but not to the same bits.
+    Changed(String),
+    /// Failed to encode or decode.
+    Failed(Error),
+}
+
+impl From<Result<Option<String>, Error>> for RoundTrip {
+    fn from(result: Result<Option<String>, Error>) -> Self {
+        match result {
+            Ok(None) => RoundTrip::Exact,
+            Ok(Some(change)) => RoundTrip::Changed(change),
+            Err(e) => RoundTrip::Failed(e),
+        }
+    }
+}
+
+/// Encode `data` with `format` and check every float decodes back to the exact same bits.
+///
+/// `PartialEq` isn't enough: `NaN != NaN` and `0.0 == -0.0`.
+pub fn check(format: Format, data: &NumericData) -> RoundTrip {
+    (|| {
+        let encoded = format.try_encode(std::slice::from_ref(data))?;
+        let decoded = format
+            .try_decode::<NumericData>(&encoded)?
+            .pop()
+            .ok_or("nothing decoded")?;
+        let (original, decoded) = (data.bits(), decoded.bits());
+        if original.len() != decoded.len() {
+            return Err("decoded data differs in length".into());
+        }
+
+        let changed = original
+            .iter()
+            .zip(&decoded)
+            .filter(|(original, decoded)| original != decoded)
+            .count();
+        Ok((changed > 0).then(|| format!("{changed} of {} values changed", original.len())))
+    })()
+    .into()
+}
+
+/// Round-trip every special value on its own with every format, in the order of [`Format::ALL`].
+pub fn check_special_values() -> Vec<(String, Vec<RoundTrip>)> {
+    let f64s = SPECIAL_F64.iter().map(|(name, value)| {
+        let results = Format::ALL
+            .iter()
+            .map(|format| check_value(*format, *value, f64::to_bits))
+            .collect();
+        (format!("f64 {name}"), results)
+    });
+    let f32s = SPECIAL_F32.iter().map(|(name, value)| {
+        let results = Format::ALL
+            .iter()
+            .map(|format| check_value(*format, *value, |value| value.to_bits() as u64))
+            .collect();
+        (format!("f32 {name}"), results)
+    });
+
+    f64s.chain(f32s).collect()
+}
+
+fn check_value<T>(format: Format, value: T, bits: impl Fn(T) -> u64) -> RoundTrip
+where
+    T: Serialize + for<'de> Deserialize<'de> + Type + Clone + Copy + std::fmt::Debug,
+{
+    (|| {
+        let encoded = format.try_encode(&[value])?;
+        let decoded = format
+            .try_decode::<T>(&encoded)?
+            .pop()
+            .ok_or("nothing decoded")?;
+        Ok((bits(decoded) != bits(value)).then(|| {
+            format!(
+                "{value:?} ({:#x}) became {decoded:?} ({:#x})",
+                bits(value),
+                bits(decoded)
+            )
+        }))
+    })()
+    .into()
+}
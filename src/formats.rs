@@ -1,11 +1,15 @@
 use crate::data::{BigData, SmallData};
+use crate::limits::Limits;
 use crate::vector_data::{BigVectorData, SmallVectorData};
-use serde::{Deserialize, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use zvariant::{
     serialized::{Context, Data},
-    to_bytes, Endian,
+    to_bytes, to_bytes_for_signature, Endian, Signature, Type,
 };
 
+/// Error of the fallible (`try_*`) encode and decode functions.
+pub type Error = Box<dyn std::error::Error + Send + Sync>;
+
 // Helper struct for BSON which can't handle arrays at the top level.
 #[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
 pub struct BsonWrapper<D> {
@@ -65,6 +69,37 @@ impl Bson {
             bson::de::deserialize_from_slice(bytes).unwrap();
         wrapper.data
     }
+
+    // Generic versions of the above, for any other payload type.
+    pub fn encode<T: Serialize + Clone>(data: &[T]) -> Vec<u8> {
+        Self::try_encode(data).unwrap()
+    }
+
+    pub fn decode<'a, T: Deserialize<'a>>(bytes: &'a [u8]) -> Vec<T> {
+        Self::try_decode(bytes).unwrap()
+    }
+
+    pub fn try_encode<T: Serialize + Clone>(data: &[T]) -> Result<Vec<u8>, Error> {
+        let wrapper = BsonWrapper {
+            data: data.to_vec(),
+        };
+        Ok(bson::ser::serialize_to_vec(&wrapper)?)
+    }
+
+    pub fn try_decode<'a, T: Deserialize<'a>>(bytes: &'a [u8]) -> Result<Vec<T>, Error> {
+        let wrapper: BsonWrapper<T> = bson::de::deserialize_from_slice(bytes)?;
+        Ok(wrapper.data)
+    }
+
+    /// [`Bson::try_decode`] within `limits`. Only the size limit applies, as the bson crate keeps
+    /// its raw deserializer private.
+    pub fn try_decode_with_limits<'a, T: Deserialize<'a>>(
+        bytes: &'a [u8],
+        limits: &Limits,
+    ) -> Result<Vec<T>, Error> {
+        limits.check_size(bytes)?;
+        Self::try_decode(bytes)
+    }
 }
 
 // JSON format implementation
@@ -103,6 +138,49 @@ impl Json {
     pub fn decode_small_vector(bytes: &[u8]) -> Vec<SmallVectorData> {
         serde_json::from_slice(bytes).unwrap()
     }
+
+    pub fn encode<T: Serialize>(data: &[T]) -> Vec<u8> {
+        Self::try_encode(data).unwrap()
+    }
+
+    pub fn decode<'a, T: Deserialize<'a>>(bytes: &'a [u8]) -> Vec<T> {
+        Self::try_decode(bytes).unwrap()
+    }
+
+    pub fn try_encode<T: Serialize>(data: &[T]) -> Result<Vec<u8>, Error> {
+        Ok(serde_json::to_string(&data)?.into_bytes())
+    }
+
+    pub fn try_decode<'a, T: Deserialize<'a>>(bytes: &'a [u8]) -> Result<Vec<T>, Error> {
+        Ok(serde_json::from_slice(bytes)?)
+    }
+
+    /// [`Json::try_decode`] within `limits`.
+    pub fn try_decode_with_limits<'a, T: Deserialize<'a>>(
+        bytes: &'a [u8],
+        limits: &Limits,
+    ) -> Result<Vec<T>, Error> {
+        limits.check_size(bytes)?;
+        let mut deserializer = serde_json::Deserializer::from_slice(bytes);
+        let decoded = limits.deserialize(&mut deserializer)?;
+        deserializer.end()?;
+
+        Ok(decoded)
+    }
+
+    /// [`Json::try_decode`] for input known to be valid UTF-8, e.g. written by this process. The
+    /// strings aren't checked, which none of the other formats can skip.
+    ///
+    /// # Safety
+    ///
+    /// `bytes` must be valid UTF-8.
+    pub unsafe fn try_decode_trusted<'a, T: Deserialize<'a>>(
+        bytes: &'a [u8],
+    ) -> Result<Vec<T>, Error> {
+        Ok(serde_json::from_str(unsafe {
+            std::str::from_utf8_unchecked(bytes)
+        })?)
+    }
 }
 
 // SIMD-JSON format implementation
@@ -141,6 +219,33 @@ impl SimdJson {
     pub fn decode_small_vector(bytes: &mut [u8]) -> Vec<SmallVectorData> {
         simd_json::from_slice(bytes).unwrap()
     }
+
+    pub fn encode<T: Serialize>(data: &[T]) -> Vec<u8> {
+        Self::try_encode(data).unwrap()
+    }
+
+    pub fn decode<'a, T: Deserialize<'a>>(bytes: &'a mut [u8]) -> Vec<T> {
+        Self::try_decode(bytes).unwrap()
+    }
+
+    pub fn try_encode<T: Serialize>(data: &[T]) -> Result<Vec<u8>, Error> {
+        Ok(simd_json::to_string(&data)?.into_bytes())
+    }
+
+    pub fn try_decode<'a, T: Deserialize<'a>>(bytes: &'a mut [u8]) -> Result<Vec<T>, Error> {
+        Ok(simd_json::from_slice(bytes)?)
+    }
+
+    /// [`SimdJson::try_decode`] within `limits`.
+    pub fn try_decode_with_limits<'a, T: Deserialize<'a>>(
+        bytes: &'a mut [u8],
+        limits: &Limits,
+    ) -> Result<Vec<T>, Error> {
+        limits.check_size(bytes)?;
+        let mut deserializer = simd_json::Deserializer::from_slice(bytes)?;
+
+        Ok(limits.deserialize(&mut deserializer)?)
+    }
 }
 
 // D-Bus format implementation
@@ -155,6 +260,11 @@ impl DBus {
         }
     }
 
+    /// The serialization context, for encoding values of other types.
+    pub fn context(&self) -> Context {
+        self.context
+    }
+
     pub fn encode_big(&self, data: &[BigData<'_>]) -> Data<'static, 'static> {
         to_bytes(self.context, &data.to_vec()).unwrap()
     }
@@ -194,6 +304,51 @@ impl DBus {
         let (decoded, _): (Vec<SmallVectorData>, _) = encoded.deserialize().unwrap();
         decoded
     }
+
+    pub fn encode<T: Serialize + Type + Clone>(&self, data: &[T]) -> Data<'static, 'static> {
+        self.try_encode(data).unwrap()
+    }
+
+    pub fn decode<'a, T: Deserialize<'a> + Type>(&self, encoded: &'a Data<'a, 'static>) -> Vec<T> {
+        self.try_decode(encoded).unwrap()
+    }
+
+    pub fn try_encode<T: Serialize + Type + Clone>(
+        &self,
+        data: &[T],
+    ) -> Result<Data<'static, 'static>, Error> {
+        Ok(to_bytes(self.context, &data.to_vec())?)
+    }
+
+    pub fn try_decode<'a, T: Deserialize<'a> + Type>(
+        &self,
+        encoded: &'a Data<'a, 'static>,
+    ) -> Result<Vec<T>, Error> {
+        let (decoded, _): (Vec<T>, _) = encoded.deserialize()?;
+        Ok(decoded)
+    }
+
+    /// [`DBus::try_decode`] within `limits`. Only the size limit applies, as zvariant keeps its
+    /// deserializer private, but D-Bus has its own limits on array sizes and nesting.
+    pub fn try_decode_with_limits<'a, T: Deserialize<'a> + Type>(
+        &self,
+        encoded: &'a Data<'a, 'static>,
+        limits: &Limits,
+    ) -> Result<Vec<T>, Error> {
+        limits.check_size(encoded.bytes())?;
+        self.try_decode(encoded)
+    }
+
+    /// Wrap encoded bytes, e.g. read from elsewhere, for decoding.
+    pub fn data<'a>(&self, bytes: &'a [u8]) -> Data<'a, 'static> {
+        Data::new(bytes, self.context)
+    }
+}
+
+impl Default for DBus {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 // CBOR removed - ciborium has serde trait limitations preventing zero-copy with &str fields
@@ -211,11 +366,11 @@ impl Bincode {
     }
 
     pub fn encode_big(&self, data: &[BigData<'_>]) -> Vec<u8> {
-        bincode::serde::encode_to_vec(&data, self.config).unwrap()
+        bincode::serde::encode_to_vec(data, self.config).unwrap()
     }
 
     pub fn encode_small(&self, data: &[SmallData<'_>]) -> Vec<u8> {
-        bincode::serde::encode_to_vec(&data, self.config).unwrap()
+        bincode::serde::encode_to_vec(data, self.config).unwrap()
     }
 
     pub fn decode_big<'a>(&self, bytes: &'a [u8]) -> Vec<BigData<'a>> {
@@ -230,11 +385,11 @@ impl Bincode {
     }
 
     pub fn encode_big_vector(&self, data: &[BigVectorData<'_>]) -> Vec<u8> {
-        bincode::serde::encode_to_vec(&data, self.config).unwrap()
+        bincode::serde::encode_to_vec(data, self.config).unwrap()
     }
 
     pub fn encode_small_vector(&self, data: &[SmallVectorData]) -> Vec<u8> {
-        bincode::serde::encode_to_vec(&data, self.config).unwrap()
+        bincode::serde::encode_to_vec(data, self.config).unwrap()
     }
 
     pub fn decode_big_vector<'a>(&self, bytes: &'a [u8]) -> Vec<BigVectorData<'a>> {
@@ -246,6 +401,41 @@ impl Bincode {
         let (decoded, _) = bincode::serde::borrow_decode_from_slice(bytes, self.config).unwrap();
         decoded
     }
+
+    pub fn encode<T: Serialize>(&self, data: &[T]) -> Vec<u8> {
+        self.try_encode(data).unwrap()
+    }
+
+    pub fn decode<'a, T: Deserialize<'a>>(&self, bytes: &'a [u8]) -> Vec<T> {
+        self.try_decode(bytes).unwrap()
+    }
+
+    pub fn try_encode<T: Serialize>(&self, data: &[T]) -> Result<Vec<u8>, Error> {
+        Ok(bincode::serde::encode_to_vec(data, self.config)?)
+    }
+
+    pub fn try_decode<'a, T: Deserialize<'a>>(&self, bytes: &'a [u8]) -> Result<Vec<T>, Error> {
+        let (decoded, _) = bincode::serde::borrow_decode_from_slice(bytes, self.config)?;
+        Ok(decoded)
+    }
+
+    /// [`Bincode::try_decode`] within `limits`.
+    pub fn try_decode_with_limits<'a, T: Deserialize<'a>>(
+        &self,
+        bytes: &'a [u8],
+        limits: &Limits,
+    ) -> Result<Vec<T>, Error> {
+        limits.check_size(bytes)?;
+        let mut decoder = bincode::serde::BorrowedSerdeDecoder::from_slice(bytes, self.config, ());
+
+        Ok(limits.deserialize(decoder.as_deserializer())?)
+    }
+}
+
+impl Default for Bincode {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 // Bitcode format implementation
@@ -284,6 +474,32 @@ impl Bitcode {
     pub fn decode_small_vector(bytes: &[u8]) -> Vec<SmallVectorData> {
         bitcode::deserialize(bytes).unwrap()
     }
+
+    pub fn encode<T: Serialize>(data: &[T]) -> Vec<u8> {
+        Self::try_encode(data).unwrap()
+    }
+
+    pub fn decode<'a, T: Deserialize<'a>>(bytes: &'a [u8]) -> Vec<T> {
+        Self::try_decode(bytes).unwrap()
+    }
+
+    pub fn try_encode<T: Serialize>(data: &[T]) -> Result<Vec<u8>, Error> {
+        Ok(bitcode::serialize(&data)?)
+    }
+
+    pub fn try_decode<'a, T: Deserialize<'a>>(bytes: &'a [u8]) -> Result<Vec<T>, Error> {
+        Ok(bitcode::deserialize(bytes)?)
+    }
+
+    /// [`Bitcode::try_decode`] within `limits`. Only the size limit applies, as bitcode doesn't
+    /// decode through a serde `Deserializer`.
+    pub fn try_decode_with_limits<'a, T: Deserialize<'a>>(
+        bytes: &'a [u8],
+        limits: &Limits,
+    ) -> Result<Vec<T>, Error> {
+        limits.check_size(bytes)?;
+        Self::try_decode(bytes)
+    }
 }
 
 // Postcard format implementation
@@ -322,4 +538,244 @@ impl Postcard {
     pub fn decode_small_vector(bytes: &[u8]) -> Vec<SmallVectorData> {
         postcard::from_bytes(bytes).unwrap()
     }
+
+    pub fn encode<T: Serialize>(data: &[T]) -> Vec<u8> {
+        Self::try_encode(data).unwrap()
+    }
+
+    pub fn decode<'a, T: Deserialize<'a>>(bytes: &'a [u8]) -> Vec<T> {
+        Self::try_decode(bytes).unwrap()
+    }
+
+    pub fn try_encode<T: Serialize>(data: &[T]) -> Result<Vec<u8>, Error> {
+        Ok(postcard::to_allocvec(&data)?)
+    }
+
+    pub fn try_decode<'a, T: Deserialize<'a>>(bytes: &'a [u8]) -> Result<Vec<T>, Error> {
+        Ok(postcard::from_bytes(bytes)?)
+    }
+
+    /// [`Postcard::try_decode`] within `limits`.
+    pub fn try_decode_with_limits<'a, T: Deserialize<'a>>(
+        bytes: &'a [u8],
+        limits: &Limits,
+    ) -> Result<Vec<T>, Error> {
+        limits.check_size(bytes)?;
+        let mut deserializer = postcard::Deserializer::from_bytes(bytes);
+
+        Ok(limits.deserialize(&mut deserializer)?)
+    }
+}
+
+/// Every format, for running the same payload through each of them.
+///
+/// This only handles owned payloads: borrowing payloads need the encoded bytes (or D-Bus `Data`) to
+/// outlive the decoded value, so they go through [`Encoded`] or the format types directly.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Format {
+    Json,
+    SimdJson,
+    DBus,
+    Bson,
+    Bincode,
+    Bitcode,
+    Postcard,
+}
+
+impl Format {
+    /// All formats, in the order of the size report.
+    pub const ALL: [Format; 7] = [
+        Format::Json,
+        Format::SimdJson,
+        Format::DBus,
+        Format::Bson,
+        Format::Bincode,
+        Format::Bitcode,
+        Format::Postcard,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Format::Json => "JSON",
+            Format::SimdJson => "SIMD-JSON",
+            Format::DBus => "D-Bus",
+            Format::Bson => "BSON",
+            Format::Bincode => "Bincode",
+            Format::Bitcode => "Bitcode",
+            Format::Postcard => "Postcard",
+        }
+    }
+
+    pub fn try_encode<T: Serialize + DBusSignature + Clone>(
+        self,
+        data: &[T],
+    ) -> Result<Vec<u8>, Error> {
+        match self {
+            Format::Json => Json::try_encode(data),
+            Format::SimdJson => SimdJson::try_encode(data),
+            Format::DBus => {
+                let signature = Signature::array(T::dbus_signature()?);
+                let encoded = to_bytes_for_signature(DBus::new().context, signature, data)?;
+                Ok(encoded.bytes().to_vec())
+            }
+            Format::Bson => Bson::try_encode(data),
+            Format::Bincode => Bincode::new().try_encode(data),
+            Format::Bitcode => Bitcode::try_encode(data),
+            Format::Postcard => Postcard::try_encode(data),
+        }
+    }
+
+    pub fn try_decode<T: DeserializeOwned + DBusSignature>(
+        self,
+        bytes: &[u8],
+    ) -> Result<Vec<T>, Error> {
+        match self {
+            Format::Json => Json::try_decode(bytes),
+            // SIMD-JSON decodes in place so it needs its own copy.
+            Format::SimdJson => SimdJson::try_decode(&mut bytes.to_vec()),
+            Format::DBus => {
+                let signature = Signature::array(T::dbus_signature()?);
+                let (decoded, _) = DBus::new()
+                    .data(bytes)
+                    .deserialize_for_signature(signature)?;
+                Ok(decoded)
+            }
+            Format::Bson => Bson::try_decode(bytes),
+            Format::Bincode => Bincode::new().try_decode(bytes),
+            Format::Bitcode => Bitcode::try_decode(bytes),
+            Format::Postcard => Postcard::try_decode(bytes),
+        }
+    }
+
+    /// [`Format::try_decode`] within `limits`, as far as the format supports them (see
+    /// [`crate::limits`]).
+    pub fn try_decode_with_limits<T: DeserializeOwned + DBusSignature>(
+        self,
+        bytes: &[u8],
+        limits: &Limits,
+    ) -> Result<Vec<T>, Error> {
+        match self {
+            Format::Json => Json::try_decode_with_limits(bytes, limits),
+            Format::SimdJson => SimdJson::try_decode_with_limits(&mut bytes.to_vec(), limits),
+            Format::DBus => {
+                limits.check_size(bytes)?;
+                self.try_decode(bytes)
+            }
+            Format::Bson => Bson::try_decode_with_limits(bytes, limits),
+            Format::Bincode => Bincode::new().try_decode_with_limits(bytes, limits),
+            Format::Bitcode => Bitcode::try_decode_with_limits(bytes, limits),
+            Format::Postcard => Postcard::try_decode_with_limits(bytes, limits),
+        }
+    }
+
+    /// Whether the depth and length limits apply to the format, besides the size limit.
+    pub fn supports_limits(self) -> bool {
+        matches!(
+            self,
+            Format::Json | Format::SimdJson | Format::Bincode | Format::Postcard
+        )
+    }
+
+    /// Decode `bytes`, a `T` payload encoded with this format, and encode it with `to`.
+    ///
+    /// This goes through `T` rather than a dynamic value, so it works for the formats that need the
+    /// type to decode. Whatever the two formats can't both represent is lost or fails on the way.
+    pub fn try_convert<T>(self, bytes: &[u8], to: Format) -> Result<Vec<u8>, Error>
+    where
+        T: Serialize + DeserializeOwned + DBusSignature + Clone,
+    {
+        to.try_encode(&self.try_decode::<T>(bytes)?)
+    }
+
+    /// Encode `data` and decode it back, checking that nothing was lost on the way.
+    pub fn try_roundtrip<T>(self, data: &[T]) -> Result<(), Error>
+    where
+        T: Serialize + DeserializeOwned + DBusSignature + Clone + PartialEq,
+    {
+        let encoded = self.try_encode(data)?;
+        if self.try_decode::<T>(&encoded)? != data {
+            return Err("decoded data differs from the original".into());
+        }
+
+        Ok(())
+    }
+
+    /// [`Format::try_roundtrip`] for payloads borrowing from the encoded bytes. `decodes_to_data`
+    /// decodes them and compares the result to `data`, since a borrowing `T` can only be named
+    /// with the lifetime of `data` here.
+    pub fn try_roundtrip_borrowed<T>(
+        self,
+        data: &[T],
+        decodes_to_data: impl FnOnce(&mut Encoded) -> Result<bool, Error>,
+    ) -> Result<(), Error>
+    where
+        T: Serialize + DBusSignature + Clone,
+    {
+        let mut encoded = Encoded::new(self, self.try_encode(data)?);
+        if !decodes_to_data(&mut encoded)? {
+            return Err("decoded data differs from the original".into());
+        }
+
+        Ok(())
+    }
+}
+
+/// Bytes encoded with one format, held so that payloads decoded from them can borrow from them:
+/// as D-Bus `Data` for D-Bus, and with room for the copy SIMD-JSON decodes in place.
+pub struct Encoded {
+    format: Format,
+    data: Data<'static, 'static>,
+    simd_json: Vec<u8>,
+}
+
+impl Encoded {
+    pub fn new(format: Format, bytes: Vec<u8>) -> Self {
+        Self {
+            format,
+            data: Data::new(bytes, DBus::new().context),
+            simd_json: vec![],
+        }
+    }
+
+    pub fn bytes(&self) -> &[u8] {
+        self.data.bytes()
+    }
+
+    /// Decode the bytes as `T`, which may borrow from them.
+    pub fn try_decode<'a, T: Deserialize<'a> + DBusSignature>(
+        &'a mut self,
+    ) -> Result<Vec<T>, Error> {
+        let bytes = self.data.bytes();
+        match self.format {
+            Format::Json => Json::try_decode(bytes),
+            // Copied every time, as decoding rewrites the bytes.
+            Format::SimdJson => {
+                self.simd_json = bytes.to_vec();
+                SimdJson::try_decode(&mut self.simd_json)
+            }
+            Format::DBus => {
+                let signature = Signature::array(T::dbus_signature()?);
+                let (decoded, _) = self.data.deserialize_for_signature(signature)?;
+                Ok(decoded)
+            }
+            Format::Bson => Bson::try_decode(bytes),
+            Format::Bincode => Bincode::new().try_decode(bytes),
+            Format::Bitcode => Bitcode::try_decode(bytes),
+            Format::Postcard => Postcard::try_decode(bytes),
+        }
+    }
+}
+
+/// The D-Bus signature of a payload type, if D-Bus can represent it.
+///
+/// Every [`Type`] has one. Payloads that can't implement `Type` (e.g. enums whose variants carry
+/// different data) implement this returning why instead, so they can still go through [`Format`].
+pub trait DBusSignature {
+    fn dbus_signature() -> Result<Signature, Error>;
+}
+
+impl<T: Type + ?Sized> DBusSignature for T {
+    fn dbus_signature() -> Result<Signature, Error> {
+        Ok(T::SIGNATURE.clone())
+    }
 }
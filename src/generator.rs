@@ -0,0 +1,244 @@
+use crate::{
+    data::{BigData, SmallData},
+    formats::Error,
+    owned_data::{OwnedBigData, OwnedSmallData},
+};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use std::{collections::HashMap, env, ops::Range};
+
+/// Environment variable used to override the seed of [`GeneratorConfig::from_env`].
+pub const SEED_ENV: &str = "JSON_VS_BIN_SEED";
+
+/// Seed used when none is given.
+pub const DEFAULT_SEED: u64 = 0x5eed;
+
+// Characters used for generated keys and text. Keeping to ASCII alphanumerics means no format
+// has to escape anything, which keeps the comparison with the static payloads fair.
+const KEY_CHARS: &[u8] = b"abcdefghijklmnopqrstuvwxyz0123456789_";
+const TEXT_CHARS: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789     ";
+
+/// How the length of generated map keys is distributed.
+#[derive(Debug, Clone, PartialEq)]
+pub enum KeyLength {
+    /// Every key has the same length.
+    Fixed(usize),
+    /// Lengths are drawn uniformly from the range.
+    Uniform(Range<usize>),
+    /// Short keys are common and long ones rare: each extra character beyond `min` is added with
+    /// probability `p`, up to `max`.
+    Geometric { min: usize, max: usize, p: f64 },
+}
+
+/// Shape of the generated payloads.
+///
+/// The ranges are sampled as they are, so [`Generator`] panics on an empty one, or on a
+/// [`KeyLength::Geometric`] `p` outside `0..=1`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GeneratorConfig {
+    pub seed: u64,
+    /// Length of free-text fields (`name`, `description`, ...).
+    pub string_len: Range<usize>,
+    /// Number of entries in each map. Colliding keys are dropped so maps may end up smaller.
+    pub map_size: Range<usize>,
+    pub key_len: KeyLength,
+    /// Range of the map values.
+    pub values: Range<u32>,
+    /// Range of the 64-bit identifiers (`user_id`, `session_id`, `timestamp`, ...).
+    pub ids: Range<u64>,
+}
+
+impl GeneratorConfig {
+    /// The default shape, with the seed taken from `JSON_VS_BIN_SEED` if set.
+    pub fn from_env() -> Result<Self, Error> {
+        let seed = match env::var(SEED_ENV) {
+            Ok(seed) => seed
+                .trim()
+                .parse()
+                .map_err(|e| format!("{SEED_ENV}: {e}: `{seed}`"))?,
+            Err(_) => DEFAULT_SEED,
+        };
+
+        Ok(Self {
+            seed,
+            ..Self::default()
+        })
+    }
+}
+
+impl Default for GeneratorConfig {
+    fn default() -> Self {
+        // Roughly the same magnitudes as the static payloads in `data`.
+        Self {
+            seed: DEFAULT_SEED,
+            string_len: 8..256,
+            map_size: 50..150,
+            key_len: KeyLength::Geometric {
+                min: 3,
+                max: 24,
+                p: 0.8,
+            },
+            values: 0..1000,
+            ids: 0..u32::MAX as u64,
+        }
+    }
+}
+
+/// Deterministic generator of varied payload instances.
+///
+/// The same config (including the seed) always yields the same instances.
+pub struct Generator {
+    config: GeneratorConfig,
+    rng: ChaCha8Rng,
+}
+
+impl Generator {
+    pub fn new(config: GeneratorConfig) -> Self {
+        let rng = ChaCha8Rng::seed_from_u64(config.seed);
+
+        Self { config, rng }
+    }
+
+    pub fn config(&self) -> &GeneratorConfig {
+        &self.config
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.config.seed
+    }
+
+    /// Generate `big` instances of [`BigData`] and `small` instances of [`SmallData`].
+    pub fn generate(&mut self, big: usize, small: usize) -> Payloads {
+        let big = (0..big).map(|_| self.big()).collect();
+        let small = (0..small).map(|_| self.small()).collect();
+
+        Payloads {
+            seed: self.config.seed,
+            big,
+            small,
+        }
+    }
+
+    fn big(&mut self) -> OwnedBigData {
+        OwnedBigData {
+            user_id: self.id(),
+            status_code: self.rng.random(),
+            is_active: self.rng.random(),
+            name: self.text(),
+            retry_count: self.rng.random(),
+            description: self.text(),
+            properties: self.map(),
+            priority: self.rng.random(),
+            message: self.text(),
+            sequence_num: self.rng.random(),
+            metadata: self.map(),
+            session_id: self.id(),
+            error_code: self.rng.random(),
+            is_verified: self.rng.random(),
+            title: self.text(),
+            attempt_count: self.rng.random(),
+            content: self.text(),
+            attributes: self.map(),
+            level: self.rng.random(),
+            details: self.text(),
+            request_id: self.rng.random(),
+            headers: self.map(),
+            timestamp: self.id(),
+            response_code: self.rng.random(),
+            is_complete: self.rng.random(),
+            summary: self.text(),
+            max_retries: self.rng.random(),
+            body: self.text(),
+            tags: self.map(),
+        }
+    }
+
+    fn small(&mut self) -> OwnedSmallData {
+        OwnedSmallData {
+            id: self.id(),
+            status: self.rng.random(),
+            enabled: self.rng.random(),
+            name: self.text(),
+            retries: self.rng.random(),
+            description: self.text(),
+            config: self.map(),
+        }
+    }
+
+    fn id(&mut self) -> u64 {
+        self.rng.random_range(self.config.ids.clone())
+    }
+
+    fn text(&mut self) -> String {
+        let len = self.rng.random_range(self.config.string_len.clone());
+
+        self.chars(TEXT_CHARS, len)
+    }
+
+    fn key(&mut self) -> String {
+        let len = match self.config.key_len {
+            KeyLength::Fixed(len) => len,
+            KeyLength::Uniform(ref range) => self.rng.random_range(range.clone()),
+            KeyLength::Geometric { min, max, p } => {
+                let mut len = min;
+                while len < max && self.rng.random_bool(p) {
+                    len += 1;
+                }
+                len
+            }
+        };
+
+        self.chars(KEY_CHARS, len)
+    }
+
+    fn map(&mut self) -> HashMap<String, u32> {
+        let len = self.rng.random_range(self.config.map_size.clone());
+        let mut map = HashMap::with_capacity(len);
+        for _ in 0..len {
+            let key = self.key();
+            let value = self.rng.random_range(self.config.values.clone());
+            map.entry(key).or_insert(value);
+        }
+
+        map
+    }
+
+    fn chars(&mut self, alphabet: &[u8], len: usize) -> String {
+        (0..len)
+            .map(|_| alphabet[self.rng.random_range(0..alphabet.len())] as char)
+            .collect()
+    }
+}
+
+/// Generated payloads.
+///
+/// The payload types borrow their strings, so the generated instances are stored in their owned
+/// form and borrowed through [`Payloads::big_data`] and [`Payloads::small_data`].
+pub struct Payloads {
+    seed: u64,
+    big: Vec<OwnedBigData>,
+    small: Vec<OwnedSmallData>,
+}
+
+impl Payloads {
+    /// The seed these payloads were generated from.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn big_data(&self) -> Vec<BigData<'_>> {
+        self.big.iter().map(OwnedBigData::as_borrowed).collect()
+    }
+
+    pub fn small_data(&self) -> Vec<SmallData<'_>> {
+        self.small.iter().map(OwnedSmallData::as_borrowed).collect()
+    }
+
+    pub fn owned_big_data(&self) -> &[OwnedBigData] {
+        &self.big
+    }
+
+    pub fn owned_small_data(&self) -> &[OwnedSmallData] {
+        &self.small
+    }
+}
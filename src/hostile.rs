@@ -0,0 +1,210 @@
+//! Hostile input: small payloads crafted to make a decoder allocate or recurse far more than their
+//! size, to see how much memory and time each decoder spends before failing, with and without
+//! [`Limits`].
+//!
+//! Memory is measured by [`CountingAllocator`], which the binary installs as its global allocator
+//! (it reads zero otherwise) and which only counts once [`probe`] starts it, so that the other
+//! modes don't pay for counting. Decoders without a nesting limit overflow the stack on deep input,
+//! which aborts the process, so the attacks are meant to be run in a child process.
+use crate::{
+    formats::{Error, Format},
+    limits::Limits,
+    tree_data::Node,
+    vector_data::SmallVectorData,
+};
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    mem,
+    sync::atomic::{AtomicBool, AtomicIsize, Ordering},
+    thread,
+    time::{Duration, Instant},
+};
+
+/// Number of elements the length bombs claim.
+pub const CLAIMED_LEN: usize = 4_000_000_000;
+
+/// Depth of the deeply nested payloads.
+pub const NESTING_DEPTH: usize = 100_000;
+
+// Stack of the thread encoding deeply nested payloads, as the encoders recurse too: enough for
+// the hungriest of them at `NESTING_DEPTH` (64 MiB isn't). Only the pages used are committed.
+const CRAFT_STACK_SIZE: usize = 256 << 20;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Attack {
+    /// A `Vec<SensorReading>` whose length prefix claims [`CLAIMED_LEN`] readings, followed by
+    /// none.
+    LengthBomb,
+    /// A [`Node`] tree nested [`NESTING_DEPTH`] levels deep.
+    DeepNesting,
+}
+
+impl Attack {
+    pub const ALL: [Attack; 2] = [Attack::LengthBomb, Attack::DeepNesting];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Attack::LengthBomb => "length-bomb",
+            Attack::DeepNesting => "deep-nesting",
+        }
+    }
+
+    /// The hostile payload for `format`, or why there's none.
+    pub fn craft(self, format: Format) -> Result<Vec<u8>, Error> {
+        match self {
+            Attack::LengthBomb => length_bomb(format),
+            Attack::DeepNesting => {
+                // Dropping deep trees recurses too, so leak it.
+                let craft = move || {
+                    let tree = Node::chain(NESTING_DEPTH);
+                    let encoded = format.try_encode(std::slice::from_ref(&tree));
+                    mem::forget(tree);
+                    encoded
+                };
+                thread::Builder::new()
+                    .stack_size(CRAFT_STACK_SIZE)
+                    .spawn(craft)?
+                    .join()
+                    .map_err(|_| "crafting thread panicked")?
+            }
+        }
+    }
+
+    /// Decode `bytes` as the payload the attack targets, within `limits`.
+    pub fn decode(self, format: Format, bytes: &[u8], limits: &Limits) -> Result<(), Error> {
+        match self {
+            Attack::LengthBomb => {
+                format.try_decode_with_limits::<SmallVectorData>(bytes, limits)?;
+            }
+            Attack::DeepNesting => {
+                // Don't let dropping a tree the decoder accepted overflow the stack.
+                mem::forget(format.try_decode_with_limits::<Node>(bytes, limits)?);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+// A `[SmallVectorData]` of one element whose readings claim `CLAIMED_LEN` elements.
+fn length_bomb(format: Format) -> Result<Vec<u8>, Error> {
+    match format {
+        Format::Json | Format::SimdJson => {
+            Err("JSON has no length prefixes, arrays end where the input says so".into())
+        }
+        // Sequence lengths come before the elements, which are encoded one after the other, so the
+        // encoding of a `Vec` of units (which take no bytes) is only its length.
+        Format::Bincode | Format::Postcard | Format::Bitcode => {
+            format.try_encode(&[(vec![(); CLAIMED_LEN],)])
+        }
+        // The byte length of the outer array, at the start of the message.
+        Format::DBus => {
+            let mut bytes = u32::MAX.to_le_bytes().to_vec();
+            bytes.extend([0; 4]);
+            Ok(bytes)
+        }
+        // The length of the top-level document.
+        Format::Bson => {
+            let mut bytes = i32::MAX.to_le_bytes().to_vec();
+            bytes.extend(b"\x04data\x00");
+            Ok(bytes)
+        }
+    }
+}
+
+/// What decoding a hostile payload cost.
+#[derive(Debug)]
+pub struct Outcome {
+    /// Most memory allocated at once while decoding, beyond what was before.
+    pub peak_memory: usize,
+    pub time: Duration,
+    /// `Ok` if the decoder accepted the payload.
+    pub result: Result<(), Error>,
+}
+
+/// Decode `bytes`, the payload of `attack` for `format`, within `limits`.
+///
+/// Decoders without a nesting limit abort the process on deep input, see the module docs.
+pub fn probe(attack: Attack, format: Format, bytes: &[u8], limits: &Limits) -> Outcome {
+    CountingAllocator::start();
+    let start = Instant::now();
+    let result = attack.decode(format, bytes, limits);
+    let time = start.elapsed();
+    let peak_memory = CountingAllocator::peak();
+
+    Outcome {
+        peak_memory,
+        time,
+        result,
+    }
+}
+
+static COUNTING: AtomicBool = AtomicBool::new(false);
+// Relative to when counting started, so freeing earlier allocations makes it negative.
+static ALLOCATED: AtomicIsize = AtomicIsize::new(0);
+static PEAK: AtomicIsize = AtomicIsize::new(0);
+
+/// The system allocator, keeping track of the memory allocated and its peak once started.
+pub struct CountingAllocator;
+
+impl CountingAllocator {
+    /// Start counting, or start over.
+    pub fn start() {
+        ALLOCATED.store(0, Ordering::SeqCst);
+        PEAK.store(0, Ordering::SeqCst);
+        COUNTING.store(true, Ordering::SeqCst);
+    }
+
+    /// Most memory allocated at once since [`CountingAllocator::start`].
+    pub fn peak() -> usize {
+        PEAK.load(Ordering::SeqCst).max(0) as usize
+    }
+
+    fn allocated(size: usize) {
+        if COUNTING.load(Ordering::Relaxed) {
+            let allocated = ALLOCATED.fetch_add(size as isize, Ordering::SeqCst) + size as isize;
+            PEAK.fetch_max(allocated, Ordering::SeqCst);
+        }
+    }
+
+    fn freed(size: usize) {
+        if COUNTING.load(Ordering::Relaxed) {
+            ALLOCATED.fetch_sub(size as isize, Ordering::SeqCst);
+        }
+    }
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { System.alloc(layout) };
+        if !ptr.is_null() {
+            Self::allocated(layout.size());
+        }
+
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { System.alloc_zeroed(layout) };
+        if !ptr.is_null() {
+            Self::allocated(layout.size());
+        }
+
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) };
+        Self::freed(layout.size());
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = unsafe { System.realloc(ptr, layout, new_size) };
+        if !new_ptr.is_null() {
+            Self::freed(layout.size());
+            Self::allocated(new_size);
+        }
+
+        new_ptr
+    }
+}
@@ -0,0 +1,242 @@
+//! Conversion of arbitrary JSON documents to every format, to see what a given message would cost
+//! in each of them.
+//!
+//! There's no Rust type for such documents, so they go through a dynamic value instead: the
+//! self-describing formats (JSON, BSON, MessagePack, CBOR) encode [`serde_json::Value`] in their
+//! own types, D-Bus encodes a [`zvariant::Value`] (variants in arrays and `a{sv}` dicts), and the
+//! other formats encode [`Value`], a plain enum they can (de)serialize without a schema.
+use crate::formats::{self, BsonWrapper, DBusSignature, Error, Format};
+use serde::{Deserialize, Serialize};
+use std::{fs, path::Path};
+use zvariant::{Array, Dict, Signature};
+
+/// A JSON value as an externally tagged enum, for formats that need the type to decode.
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
+pub enum Value {
+    Null,
+    Bool(bool),
+    I64(i64),
+    U64(u64),
+    F64(f64),
+    String(String),
+    Array(Vec<Value>),
+    Object(Vec<(String, Value)>),
+}
+
+impl From<&serde_json::Value> for Value {
+    fn from(value: &serde_json::Value) -> Self {
+        match value {
+            serde_json::Value::Null => Value::Null,
+            serde_json::Value::Bool(b) => Value::Bool(*b),
+            serde_json::Value::Number(n) => match (n.as_i64(), n.as_u64(), n.as_f64()) {
+                (Some(i), _, _) => Value::I64(i),
+                (_, Some(u), _) => Value::U64(u),
+                (_, _, f) => Value::F64(f.unwrap_or(f64::NAN)),
+            },
+            serde_json::Value::String(s) => Value::String(s.clone()),
+            serde_json::Value::Array(array) => {
+                Value::Array(array.iter().map(Value::from).collect())
+            }
+            serde_json::Value::Object(object) => Value::Object(
+                object
+                    .iter()
+                    .map(|(key, value)| (key.clone(), Value::from(value)))
+                    .collect(),
+            ),
+        }
+    }
+}
+
+impl From<Value> for serde_json::Value {
+    fn from(value: Value) -> Self {
+        match value {
+            Value::Null => serde_json::Value::Null,
+            Value::Bool(b) => b.into(),
+            Value::I64(i) => i.into(),
+            Value::U64(u) => u.into(),
+            Value::F64(f) => f.into(),
+            Value::String(s) => s.into(),
+            Value::Array(array) => array.into_iter().map(serde_json::Value::from).collect(),
+            Value::Object(object) => serde_json::Value::Object(
+                object
+                    .into_iter()
+                    .map(|(key, value)| (key, value.into()))
+                    .collect(),
+            ),
+        }
+    }
+}
+
+impl DBusSignature for Value {
+    fn dbus_signature() -> Result<Signature, Error> {
+        Err("enum variants must all carry the same data".into())
+    }
+}
+
+/// The encoded size of a document in one format.
+#[derive(Debug)]
+pub struct Conversion {
+    pub format: &'static str,
+    /// The encoded size, or why the document can't be represented or doesn't round-trip.
+    pub size: Result<usize, Error>,
+}
+
+/// Read and parse the JSON document at `path`.
+pub fn read(path: &Path) -> Result<serde_json::Value, Error> {
+    let bytes = fs::read(path)?;
+
+    Ok(serde_json::from_slice(&bytes)?)
+}
+
+/// Convert `document` to every format, checking each one decodes back to the same document.
+pub fn convert(document: &serde_json::Value) -> Vec<Conversion> {
+    let value = Value::from(document);
+    let dynamic = |format: Format| {
+        let encoded = format.try_encode(std::slice::from_ref(&value))?;
+        let decoded = format.try_decode::<Value>(&encoded)?;
+        let decoded = decoded.into_iter().next().map(serde_json::Value::from);
+        check(document, decoded, encoded.len())
+    };
+
+    vec![
+        Conversion {
+            format: "JSON",
+            size: (|| {
+                let encoded = serde_json::to_vec(document)?;
+                check(
+                    document,
+                    Some(serde_json::from_slice(&encoded)?),
+                    encoded.len(),
+                )
+            })(),
+        },
+        Conversion {
+            format: "SIMD-JSON",
+            size: (|| {
+                let encoded = simd_json::to_vec(document)?;
+                let size = encoded.len();
+                check(
+                    document,
+                    Some(simd_json::from_slice(&mut encoded.clone())?),
+                    size,
+                )
+            })(),
+        },
+        Conversion {
+            format: "D-Bus",
+            size: (|| {
+                let dbus = formats::DBus::new();
+                let encoded = zvariant::to_bytes(dbus.context(), &to_dbus(document)?)?;
+                let (decoded, _) = encoded.deserialize()?;
+                check(document, Some(from_dbus(&decoded)?), encoded.len())
+            })(),
+        },
+        Conversion {
+            format: "BSON",
+            // BSON documents can't have an array or a scalar at the top level.
+            size: (|| {
+                let encoded = bson::serialize_to_vec(&BsonWrapper {
+                    data: vec![document],
+                })?;
+                let decoded: BsonWrapper<serde_json::Value> =
+                    bson::deserialize_from_slice(&encoded)?;
+                check(document, decoded.data.into_iter().next(), encoded.len())
+            })(),
+        },
+        Conversion {
+            format: "MessagePack",
+            size: (|| {
+                let encoded = rmp_serde::to_vec(document)?;
+                check(
+                    document,
+                    Some(rmp_serde::from_slice(&encoded)?),
+                    encoded.len(),
+                )
+            })(),
+        },
+        Conversion {
+            format: "CBOR",
+            size: (|| {
+                let mut encoded = vec![];
+                ciborium::into_writer(document, &mut encoded)?;
+                let decoded: serde_json::Value = ciborium::from_reader(&encoded[..])?;
+                check(document, Some(decoded), encoded.len())
+            })(),
+        },
+        Conversion {
+            format: "Bincode",
+            size: dynamic(Format::Bincode),
+        },
+        Conversion {
+            format: "Bitcode",
+            size: dynamic(Format::Bitcode),
+        },
+        Conversion {
+            format: "Postcard",
+            size: dynamic(Format::Postcard),
+        },
+    ]
+}
+
+fn check(
+    document: &serde_json::Value,
+    decoded: Option<serde_json::Value>,
+    size: usize,
+) -> Result<usize, Error> {
+    if decoded.as_ref() != Some(document) {
+        return Err("decoded document differs from the original".into());
+    }
+
+    Ok(size)
+}
+
+fn to_dbus(value: &serde_json::Value) -> Result<zvariant::Value<'static>, Error> {
+    let variant = |value| Ok::<_, Error>(zvariant::Value::Value(Box::new(to_dbus(value)?)));
+
+    Ok(match value {
+        serde_json::Value::Null => return Err("D-Bus has no null".into()),
+        serde_json::Value::Bool(b) => (*b).into(),
+        serde_json::Value::Number(n) => match (n.as_i64(), n.as_u64(), n.as_f64()) {
+            (Some(i), _, _) => i.into(),
+            (_, Some(u), _) => u.into(),
+            (_, _, f) => f.unwrap_or(f64::NAN).into(),
+        },
+        serde_json::Value::String(s) => s.clone().into(),
+        // Elements and values may be of any type, so they're all variants.
+        serde_json::Value::Array(array) => {
+            let mut dbus = Array::new(&Signature::Variant);
+            for value in array {
+                dbus.append(variant(value)?)?;
+            }
+            dbus.into()
+        }
+        serde_json::Value::Object(object) => {
+            let mut dbus = Dict::new(&Signature::Str, &Signature::Variant);
+            for (key, value) in object {
+                dbus.append(key.clone().into(), variant(value)?)?;
+            }
+            dbus.into()
+        }
+    })
+}
+
+fn from_dbus(value: &zvariant::Value<'_>) -> Result<serde_json::Value, Error> {
+    Ok(match value {
+        zvariant::Value::Value(value) => from_dbus(value)?,
+        zvariant::Value::Bool(b) => (*b).into(),
+        zvariant::Value::I64(i) => (*i).into(),
+        zvariant::Value::U64(u) => (*u).into(),
+        zvariant::Value::F64(f) => (*f).into(),
+        zvariant::Value::Str(s) => s.as_str().into(),
+        zvariant::Value::Array(array) => array.iter().map(from_dbus).collect::<Result<_, _>>()?,
+        zvariant::Value::Dict(dict) => serde_json::Value::Object(
+            dict.iter()
+                .map(|(key, value)| match key {
+                    zvariant::Value::Str(key) => Ok((key.to_string(), from_dbus(value)?)),
+                    _ => Err(Error::from("non-string dict key")),
+                })
+                .collect::<Result<_, _>>()?,
+        ),
+        value => return Err(format!("unexpected D-Bus value {value:?}").into()),
+    })
+}
@@ -1,6 +1,27 @@
+pub mod analysis;
+pub mod blob_data;
+pub mod columnar_data;
+pub mod compression;
+pub mod corpus;
 pub mod data;
+pub mod dictionary;
+pub mod event_data;
+pub mod evolution_data;
+pub mod float_data;
 pub mod formats;
+pub mod generator;
+pub mod hostile;
+pub mod import;
+pub mod limits;
+pub mod measure;
+pub mod ordered_data;
+pub mod owned_data;
+pub mod sweep;
+pub mod text_data;
+pub mod transform;
+pub mod tree_data;
 pub mod vector_data;
+pub mod wide_data;
 
 pub use data::{BigData, SmallData};
 pub use vector_data::{BigVectorData, SmallVectorData};
@@ -0,0 +1,472 @@
+//! Limits on what a decoder accepts, for decoding untrusted input.
+//!
+//! The input size is checked before decoding, so it applies to every format. Nesting depth and
+//! collection lengths are enforced by wrapping the format's serde `Deserializer`, which only works
+//! for formats whose crate exposes it: JSON, SIMD-JSON, Bincode and Postcard. D-Bus has its own
+//! fixed limits (arrays of at most 64 MiB, 32 nested arrays and 32 nested structs), BSON's lengths
+//! can't claim more than the input, and Bitcode checks lengths against the input before
+//! allocating.
+//!
+//! The limits used by the reports come from the `JSON_VS_BIN_LIMITS` environment variable if set,
+//! as a comma-separated list of `size=BYTES`, `depth=LEVELS` and `len=ELEMENTS`.
+use crate::formats::Error;
+use serde::de::{
+    self, DeserializeSeed, Deserializer, EnumAccess, MapAccess, SeqAccess, VariantAccess, Visitor,
+};
+use std::{cell::Cell, env, fmt};
+
+/// Environment variable overriding [`Limits::from_env`].
+pub const LIMITS_ENV: &str = "JSON_VS_BIN_LIMITS";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Limits {
+    /// Maximum input size, in bytes.
+    pub max_size: Option<usize>,
+    /// Maximum number of nested sequences, maps, structs and enums.
+    pub max_depth: Option<usize>,
+    /// Maximum number of elements of a sequence or entries of a map.
+    pub max_len: Option<usize>,
+}
+
+impl Limits {
+    /// No limits beyond those built into the formats.
+    pub const NONE: Limits = Limits {
+        max_size: None,
+        max_depth: None,
+        max_len: None,
+    };
+
+    /// The limits listed in `JSON_VS_BIN_LIMITS`, or the defaults.
+    pub fn from_env() -> Result<Self, Error> {
+        let Ok(list) = env::var(LIMITS_ENV) else {
+            return Ok(Self::default());
+        };
+
+        let mut limits = Self::NONE;
+        for limit in list.split(',') {
+            let (name, value) = limit
+                .trim()
+                .split_once('=')
+                .ok_or_else(|| format!("{LIMITS_ENV}: expected `name=value`, got `{limit}`"))?;
+            let value = Some(
+                value
+                    .parse()
+                    .map_err(|e| format!("{LIMITS_ENV}: {name}: {e}"))?,
+            );
+            match name {
+                "size" => limits.max_size = value,
+                "depth" => limits.max_depth = value,
+                "len" => limits.max_len = value,
+                _ => return Err(format!("{LIMITS_ENV}: unknown limit `{name}`").into()),
+            }
+        }
+
+        Ok(limits)
+    }
+
+    /// Fail if `bytes` is over the size limit.
+    pub fn check_size(&self, bytes: &[u8]) -> Result<(), Error> {
+        match self.max_size {
+            Some(max_size) if bytes.len() > max_size => Err(format!(
+                "input of {} bytes is over the limit of {max_size}",
+                bytes.len()
+            )
+            .into()),
+            _ => Ok(()),
+        }
+    }
+
+    /// Deserialize a `T` from `deserializer`, failing as soon as the depth or length limits are
+    /// exceeded.
+    pub fn deserialize<'de, T, D>(&self, deserializer: D) -> Result<T, D::Error>
+    where
+        T: de::Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        let state = State {
+            limits: *self,
+            depth: Cell::new(0),
+        };
+
+        T::deserialize(Limited {
+            inner: deserializer,
+            state: &state,
+        })
+    }
+}
+
+/// Generous enough for any of the payloads, while keeping hostile input from exhausting memory
+/// or the stack: 64 MiB, 128 levels (serde_json's own limit) and 1,048,576 elements.
+impl Default for Limits {
+    fn default() -> Self {
+        Self {
+            max_size: Some(64 * 1024 * 1024),
+            max_depth: Some(128),
+            max_len: Some(1 << 20),
+        }
+    }
+}
+
+impl fmt::Display for Limits {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let limit = |limit: Option<usize>| limit.map_or("none".to_string(), |l| l.to_string());
+
+        write!(
+            f,
+            "size {}, depth {}, len {}",
+            limit(self.max_size),
+            limit(self.max_depth),
+            limit(self.max_len),
+        )
+    }
+}
+
+struct State {
+    limits: Limits,
+    depth: Cell<usize>,
+}
+
+impl State {
+    // Run `f` one level deeper.
+    fn nested<T, E: de::Error>(&self, f: impl FnOnce() -> Result<T, E>) -> Result<T, E> {
+        let depth = self.depth.get() + 1;
+        if let Some(max_depth) = self.limits.max_depth {
+            if depth > max_depth {
+                return Err(E::custom(format!("nested deeper than {max_depth} levels")));
+            }
+        }
+
+        self.depth.set(depth);
+        let result = f();
+        self.depth.set(depth - 1);
+
+        result
+    }
+
+    fn check_len<E: de::Error>(&self, len: usize) -> Result<(), E> {
+        match self.limits.max_len {
+            Some(max_len) if len > max_len => {
+                Err(E::custom(format!("more than {max_len} elements")))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+// The wrappers below pass everything through to the format, wrapping in turn whatever they hand
+// out, so that every sequence, map and enum goes through `LimitedVisitor`.
+
+struct Limited<'s, D> {
+    inner: D,
+    state: &'s State,
+}
+
+macro_rules! forward_deserialize {
+    ($($method:ident($($arg:ident: $ty:ty),*);)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(
+                self,
+                $($arg: $ty,)*
+                visitor: V,
+            ) -> Result<V::Value, D::Error> {
+                self.inner.$method($($arg,)* LimitedVisitor {
+                    inner: visitor,
+                    state: self.state,
+                })
+            }
+        )*
+    };
+}
+
+impl<'de, D: Deserializer<'de>> Deserializer<'de> for Limited<'_, D> {
+    type Error = D::Error;
+
+    forward_deserialize! {
+        deserialize_any();
+        deserialize_bool();
+        deserialize_i8();
+        deserialize_i16();
+        deserialize_i32();
+        deserialize_i64();
+        deserialize_i128();
+        deserialize_u8();
+        deserialize_u16();
+        deserialize_u32();
+        deserialize_u64();
+        deserialize_u128();
+        deserialize_f32();
+        deserialize_f64();
+        deserialize_char();
+        deserialize_str();
+        deserialize_string();
+        deserialize_bytes();
+        deserialize_byte_buf();
+        deserialize_option();
+        deserialize_unit();
+        deserialize_unit_struct(name: &'static str);
+        deserialize_newtype_struct(name: &'static str);
+        deserialize_seq();
+        deserialize_tuple(len: usize);
+        deserialize_tuple_struct(name: &'static str, len: usize);
+        deserialize_map();
+        deserialize_struct(name: &'static str, fields: &'static [&'static str]);
+        deserialize_enum(name: &'static str, variants: &'static [&'static str]);
+        deserialize_identifier();
+        deserialize_ignored_any();
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.inner.is_human_readable()
+    }
+}
+
+struct LimitedSeed<'s, S> {
+    inner: S,
+    state: &'s State,
+}
+
+impl<'de, S: DeserializeSeed<'de>> DeserializeSeed<'de> for LimitedSeed<'_, S> {
+    type Value = S::Value;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<S::Value, D::Error> {
+        self.inner.deserialize(Limited {
+            inner: deserializer,
+            state: self.state,
+        })
+    }
+}
+
+struct LimitedVisitor<'s, V> {
+    inner: V,
+    state: &'s State,
+}
+
+macro_rules! forward_visit {
+    ($($method:ident($ty:ty);)*) => {
+        $(
+            fn $method<E: de::Error>(self, v: $ty) -> Result<V::Value, E> {
+                self.inner.$method(v)
+            }
+        )*
+    };
+}
+
+impl<'de, V: Visitor<'de>> Visitor<'de> for LimitedVisitor<'_, V> {
+    type Value = V::Value;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.inner.expecting(f)
+    }
+
+    forward_visit! {
+        visit_bool(bool);
+        visit_i8(i8);
+        visit_i16(i16);
+        visit_i32(i32);
+        visit_i64(i64);
+        visit_i128(i128);
+        visit_u8(u8);
+        visit_u16(u16);
+        visit_u32(u32);
+        visit_u64(u64);
+        visit_u128(u128);
+        visit_f32(f32);
+        visit_f64(f64);
+        visit_char(char);
+        visit_str(&str);
+        visit_borrowed_str(&'de str);
+        visit_string(String);
+        visit_bytes(&[u8]);
+        visit_borrowed_bytes(&'de [u8]);
+        visit_byte_buf(Vec<u8>);
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<V::Value, E> {
+        self.inner.visit_none()
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<V::Value, E> {
+        self.inner.visit_unit()
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<V::Value, D::Error> {
+        self.inner.visit_some(Limited {
+            inner: deserializer,
+            state: self.state,
+        })
+    }
+
+    fn visit_newtype_struct<D: Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> Result<V::Value, D::Error> {
+        self.inner.visit_newtype_struct(Limited {
+            inner: deserializer,
+            state: self.state,
+        })
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, seq: A) -> Result<V::Value, A::Error> {
+        // Fail on the claimed length before the visitor allocates for it.
+        if let Some(len) = seq.size_hint() {
+            self.state.check_len(len)?;
+        }
+
+        self.state.nested(|| {
+            self.inner.visit_seq(LimitedAccess {
+                inner: seq,
+                state: self.state,
+                len: 0,
+            })
+        })
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<V::Value, A::Error> {
+        if let Some(len) = map.size_hint() {
+            self.state.check_len(len)?;
+        }
+
+        self.state.nested(|| {
+            self.inner.visit_map(LimitedAccess {
+                inner: map,
+                state: self.state,
+                len: 0,
+            })
+        })
+    }
+
+    fn visit_enum<A: EnumAccess<'de>>(self, data: A) -> Result<V::Value, A::Error> {
+        self.state.nested(|| {
+            self.inner.visit_enum(LimitedAccess {
+                inner: data,
+                state: self.state,
+                len: 0,
+            })
+        })
+    }
+}
+
+// A sequence, map, enum or enum variant, counting the elements of sequences and maps.
+struct LimitedAccess<'s, A> {
+    inner: A,
+    state: &'s State,
+    len: usize,
+}
+
+impl<'de, A: SeqAccess<'de>> SeqAccess<'de> for LimitedAccess<'_, A> {
+    type Error = A::Error;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, A::Error> {
+        let next = self.inner.next_element_seed(LimitedSeed {
+            inner: seed,
+            state: self.state,
+        })?;
+        if next.is_some() {
+            self.len += 1;
+            self.state.check_len(self.len)?;
+        }
+
+        Ok(next)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.inner.size_hint()
+    }
+}
+
+impl<'de, A: MapAccess<'de>> MapAccess<'de> for LimitedAccess<'_, A> {
+    type Error = A::Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, A::Error> {
+        let next = self.inner.next_key_seed(LimitedSeed {
+            inner: seed,
+            state: self.state,
+        })?;
+        if next.is_some() {
+            self.len += 1;
+            self.state.check_len(self.len)?;
+        }
+
+        Ok(next)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, A::Error> {
+        self.inner.next_value_seed(LimitedSeed {
+            inner: seed,
+            state: self.state,
+        })
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.inner.size_hint()
+    }
+}
+
+impl<'s, 'de, A: EnumAccess<'de>> EnumAccess<'de> for LimitedAccess<'s, A> {
+    type Error = A::Error;
+    type Variant = LimitedAccess<'s, A::Variant>;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(
+        self,
+        seed: V,
+    ) -> Result<(V::Value, Self::Variant), A::Error> {
+        let (value, variant) = self.inner.variant_seed(LimitedSeed {
+            inner: seed,
+            state: self.state,
+        })?;
+
+        Ok((
+            value,
+            LimitedAccess {
+                inner: variant,
+                state: self.state,
+                len: 0,
+            },
+        ))
+    }
+}
+
+impl<'de, A: VariantAccess<'de>> VariantAccess<'de> for LimitedAccess<'_, A> {
+    type Error = A::Error;
+
+    fn unit_variant(self) -> Result<(), A::Error> {
+        self.inner.unit_variant()
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value, A::Error> {
+        self.inner.newtype_variant_seed(LimitedSeed {
+            inner: seed,
+            state: self.state,
+        })
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, len: usize, visitor: V) -> Result<V::Value, A::Error> {
+        self.inner.tuple_variant(
+            len,
+            LimitedVisitor {
+                inner: visitor,
+                state: self.state,
+            },
+        )
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, A::Error> {
+        self.inner.struct_variant(
+            fields,
+            LimitedVisitor {
+                inner: visitor,
+                state: self.state,
+            },
+        )
+    }
+}
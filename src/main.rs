@@ -1,11 +1,178 @@
 use json_vs_bin::{
+    analysis::{self, Analysis},
+    blob_data::{self, BLOB_SIZES},
+    columnar_data,
+    compression::{self, Compression},
+    corpus::{self, Corpus, Document},
     data::{BigData, SmallData},
-    formats,
+    dictionary::{self, DictionaryResult},
+    event_data::{self, Event, Outcome},
+    evolution_data::{self, Compatibility},
+    float_data::{self, NumericData, RoundTrip},
+    formats::{self, DBusSignature, Format},
+    generator::{Generator, GeneratorConfig},
+    hostile::{self, Attack, CountingAllocator},
+    import,
+    limits::{self, Limits},
+    measure,
+    ordered_data::Maps,
+    owned_data::{OwnedBigData, OwnedBigVectorData, OwnedSmallData},
+    sweep,
+    text_data::{self, Text},
+    transform,
+    tree_data::{self, Node, MAX_PROBE_DEPTH, PROBE_STACK_SIZE, SHAPES},
     vector_data::{BigVectorData, SmallVectorData},
+    wide_data::{self, RECORDS},
 };
-use std::iter;
+use serde::Serialize;
+use std::{
+    env, fs,
+    io::{self, Read, Write},
+    iter,
+    path::Path,
+    process::{self, Command},
+};
+
+// Counts allocations for `hostile-probe`, which turns counting on.
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
 
 fn main() {
+    let mut args = env::args().skip(1);
+    match args.next().as_deref() {
+        None | Some("sizes") => sizes(),
+        Some("sweep") => {
+            // Optional cap on the instance/record counts, as the top of the range needs a lot of
+            // memory.
+            let max = args
+                .next()
+                .map(|max| max.parse().unwrap_or_else(|_| usage()));
+            sweep(max.unwrap_or(usize::MAX));
+        }
+        Some("text") => text(),
+        Some("enums") => enums(),
+        Some("evolution") => evolution(),
+        Some("blobs") => blobs(),
+        Some("tree") => tree(),
+        Some("floats") => floats(),
+        Some("wide") => wide(),
+        Some("columnar") => columnar(),
+        Some("transforms") => transforms(),
+        Some("maps") => maps(),
+        Some("dictionary") => dictionary(),
+        Some("analyze") => analyze(),
+        Some("hostile") => hostile(),
+        Some("import") => {
+            let Some(path) = args.next() else { usage() };
+            import(Path::new(&path));
+        }
+        Some("convert") => {
+            let (Some(payload), Some(from), Some(to)) = (args.next(), args.next(), args.next())
+            else {
+                usage()
+            };
+            let [from, to] = [from, to].map(|format| {
+                Format::ALL
+                    .into_iter()
+                    .find(|f| f.name() == format)
+                    .unwrap_or_else(|| usage())
+            });
+            convert(&payload, from, to, args.next().as_deref().map(Path::new));
+        }
+        // Run by `tree` in a child process, as probes may overflow the stack.
+        Some("tree-probe") => {
+            let (Some(stage), Some(format), Some(depth)) = (args.next(), args.next(), args.next())
+            else {
+                usage()
+            };
+            let format = Format::ALL
+                .into_iter()
+                .find(|f| f.name() == format)
+                .unwrap_or_else(|| usage());
+            let depth = depth.parse().unwrap_or_else(|_| usage());
+            let result = match stage.as_str() {
+                "encode" => tree_data::probe_encode(format, depth),
+                "roundtrip" => tree_data::probe_roundtrip(format, depth),
+                _ => usage(),
+            };
+            if let Err(e) = result {
+                eprintln!("{e}");
+                process::exit(1);
+            }
+        }
+        // Run by `maps` in child processes, to compare the bytes encoded by different processes.
+        Some("maps-probe") => {
+            let (Some(maps), Some(format)) = (args.next(), args.next()) else {
+                usage()
+            };
+            let maps = Maps::ALL
+                .into_iter()
+                .find(|m| m.name() == maps)
+                .unwrap_or_else(|| usage());
+            let format = Format::ALL
+                .into_iter()
+                .find(|f| f.name() == format)
+                .unwrap_or_else(|| usage());
+            match maps.encode(format) {
+                Ok(encoded) => io::stdout().write_all(&encoded).unwrap(),
+                Err(e) => {
+                    eprintln!("{e}");
+                    process::exit(1);
+                }
+            }
+        }
+        // Run by `hostile` in child processes, as decoders may overflow the stack or run out of
+        // memory. Prints the input size in bytes on a first line, so that it's known even if
+        // decoding crashes, then the peak memory in bytes, the time in nanoseconds and the result.
+        // Exits with `NO_PAYLOAD_EXIT` if the attack has no payload for the format.
+        Some("hostile-probe") => {
+            let (Some(attack), Some(format), Some(limited)) =
+                (args.next(), args.next(), args.next())
+            else {
+                usage()
+            };
+            let attack = Attack::ALL
+                .into_iter()
+                .find(|a| a.name() == attack)
+                .unwrap_or_else(|| usage());
+            let format = Format::ALL
+                .into_iter()
+                .find(|f| f.name() == format)
+                .unwrap_or_else(|| usage());
+            let limits = match limited.as_str() {
+                "limited" => Limits::from_env().unwrap_or_else(|e| {
+                    eprintln!("{e}");
+                    process::exit(1);
+                }),
+                "unlimited" => Limits::NONE,
+                _ => usage(),
+            };
+            let bytes = attack.craft(format).unwrap_or_else(|e| {
+                eprintln!("{e}");
+                process::exit(NO_PAYLOAD_EXIT);
+            });
+            println!("{}", bytes.len());
+            let outcome = hostile::probe(attack, format, &bytes, &limits);
+            let result = match outcome.result {
+                Ok(()) => "accepted".to_string(),
+                Err(e) => format!("rejected: {e}"),
+            };
+            println!(
+                "{} {} {result}",
+                outcome.peak_memory,
+                outcome.time.as_nanos()
+            );
+        }
+        Some(_) => usage(),
+    }
+}
+
+fn usage() -> ! {
+    eprintln!("Usage: json-vs-bin [sizes | sweep [MAX_COUNT] | text | enums | evolution | blobs | tree | floats | wide | columnar | transforms | maps | dictionary | analyze | hostile | import FILE | convert PAYLOAD FROM TO [FILE]]");
+    process::exit(1);
+}
+
+fn sizes() {
     println!("=== Encoded Size Analysis ===\n");
 
     // Create test data matching benchmarks
@@ -246,6 +413,1217 @@ fn main() {
         most_compact_small_vector.2,
         (most_compact_small_vector.2 as f64 / json_small_vector.len() as f64) * 100.0
     );
+
+    println!();
+    seeded_sizes();
+
+    println!();
+    corpus_sizes();
+
+    println!();
+    compressed_sizes();
+
+    println!();
+    evolution();
+}
+
+// Same tables for varied, seeded instances rather than 10 copies of the same one.
+fn seeded_sizes() {
+    let config = GeneratorConfig::from_env().unwrap_or_else(|e| {
+        eprintln!("{e}");
+        process::exit(1);
+    });
+    let mut generator = Generator::new(config);
+    let payloads = generator.generate(10, 10);
+    let big_data = payloads.big_data();
+    let small_data = payloads.small_data();
+
+    println!("## Seeded Random Data (seed {})", payloads.seed());
+    println!();
+    println!("Set `JSON_VS_BIN_SEED` to reproduce or vary these results.");
+
+    let dbus = formats::DBus::new();
+    let bincode = formats::Bincode::new();
+
+    let big = [
+        ("JSON", formats::Json::encode_big(&big_data).len()),
+        ("SIMD-JSON", formats::SimdJson::encode_big(&big_data).len()),
+        ("D-Bus", dbus.encode_big(&big_data).bytes().len()),
+        ("BSON", formats::Bson::encode_big(&big_data).len()),
+        ("Bincode", bincode.encode_big(&big_data).len()),
+        ("Bitcode", formats::Bitcode::encode_big(&big_data).len()),
+        ("Postcard", formats::Postcard::encode_big(&big_data).len()),
+    ];
+    print_table("Big Payload (10 instances)", &big);
+
+    let small = [
+        ("JSON", formats::Json::encode_small(&small_data).len()),
+        (
+            "SIMD-JSON",
+            formats::SimdJson::encode_small(&small_data).len(),
+        ),
+        ("D-Bus", dbus.encode_small(&small_data).bytes().len()),
+        ("BSON", formats::Bson::encode_small(&small_data).len()),
+        ("Bincode", bincode.encode_small(&small_data).len()),
+        ("Bitcode", formats::Bitcode::encode_small(&small_data).len()),
+        (
+            "Postcard",
+            formats::Postcard::encode_small(&small_data).len(),
+        ),
+    ];
+    print_table("Small Payload (10 instances)", &small);
+}
+
+// Same tables for the standard JSON benchmark documents, if present.
+fn corpus_sizes() {
+    let dir = corpus::dir();
+
+    println!("## JSON Benchmark Corpus ({})", dir.display());
+    let corpus = match Corpus::load(&dir) {
+        Ok(corpus) if !corpus.is_empty() => corpus,
+        Ok(_) => {
+            println!();
+            println!(
+                "No corpus files found. Put `canada.json`, `citm_catalog.json` or `twitter.json` in \
+                 this directory or set `JSON_VS_BIN_CORPUS_DIR`."
+            );
+            return;
+        }
+        Err(e) => {
+            println!();
+            println!("Failed to load the corpus: {e}");
+            return;
+        }
+    };
+
+    if let Some(document) = &corpus.canada {
+        print_document_sizes(document);
+    }
+    if let Some(document) = &corpus.citm_catalog {
+        print_document_sizes(document);
+    }
+    if let Some(document) = &corpus.twitter {
+        print_document_sizes(document);
+    }
+}
+
+// Compressed sizes of the seeded big payload, whose instances all differ, and of the big vector
+// payload.
+fn compressed_sizes() {
+    let compressions = match Compression::from_env() {
+        Ok(compressions) => compressions,
+        Err(e) => {
+            println!("Failed to configure compression: {e}");
+            return;
+        }
+    };
+
+    println!("## Compressed Size");
+    println!();
+    println!(
+        "Set `{}` to choose codecs and levels, e.g. `zstd:19,gzip:9,lz4`.",
+        compression::COMPRESSION_ENV
+    );
+
+    let config = GeneratorConfig::from_env().unwrap_or_else(|e| {
+        eprintln!("{e}");
+        process::exit(1);
+    });
+    let mut generator = Generator::new(config);
+    let payloads = generator.generate(10, 0);
+    print_compressed_sizes(
+        "Seeded Big Payload (10 instances)",
+        &compression::sizes(payloads.owned_big_data(), &compressions),
+        &compressions,
+    );
+
+    // A single instance, as compressors would otherwise find every copy after the first.
+    print_compressed_sizes(
+        "Big Vector Payload (1 instance)",
+        &compression::sizes(&[OwnedBigVectorData::new()], &compressions),
+        &compressions,
+    );
+}
+
+fn print_compressed_sizes(
+    title: &str,
+    sizes: &[compression::CompressedSizes],
+    compressions: &[Compression],
+) {
+    let header = compressions
+        .iter()
+        .map(|compression| format!(" {:>10} |", compression.to_string()))
+        .collect::<String>();
+    let separator = " ---------- |".repeat(compressions.len());
+    let cell = |size: &Result<usize, _>| match size {
+        Ok(size) => format!(" {size:>10} |"),
+        Err(_) => format!(" {:>10} |", "fails"),
+    };
+
+    println!();
+    println!("### {title}");
+    println!();
+    println!("| Format      |        raw |{header}");
+    println!("| ----------- | ---------- |{separator}");
+    for sizes in sizes {
+        let cells = sizes.compressed.iter().map(cell).collect::<String>();
+        println!("| {:>11} |{}{cells}", sizes.format, cell(&sizes.raw));
+    }
+}
+
+fn print_document_sizes<T: Serialize + DBusSignature + Clone>(document: &Document<T>) {
+    let data = std::slice::from_ref(&document.data);
+    let (sizes, failures): (Vec<_>, Vec<_>) = Format::ALL
+        .iter()
+        .map(|format| (format.name(), format.try_encode(data)))
+        .partition(|(_, encoded)| encoded.is_ok());
+    let sizes = sizes
+        .into_iter()
+        .map(|(format, encoded)| (format, encoded.map_or(0, |encoded| encoded.len())))
+        .collect::<Vec<_>>();
+
+    print_table(
+        &format!(
+            "{} (original file: {} bytes)",
+            document.file_name, document.file_size
+        ),
+        &sizes,
+    );
+    for (format, encoded) in failures {
+        if let Err(e) = encoded {
+            println!("- {format} fails: {e}");
+        }
+    }
+}
+
+fn sweep(max: usize) {
+    println!("=== Payload Scaling Sweep ===");
+
+    let small = sweep::SMALL_COUNTS
+        .iter()
+        .filter(|count| **count <= max)
+        .map(|count| sweep::small(*count))
+        .collect::<Vec<_>>();
+    print_sweep("SmallData", "Instances", &small);
+
+    let big_vector = sweep::BIG_VECTOR_RECORDS
+        .iter()
+        .filter(|records| **records <= max)
+        .map(|records| sweep::big_vector(*records))
+        .collect::<Vec<_>>();
+    print_sweep("BigVectorData", "Records", &big_vector);
+}
+
+// Print the size and time curves of a sweep, one row per count and one column per format.
+fn print_sweep(payload: &str, count_label: &str, rows: &[Vec<sweep::Point>]) {
+    let Some(first) = rows.first() else {
+        return;
+    };
+    let header = first
+        .iter()
+        .map(|point| format!(" {:>11} |", point.format))
+        .collect::<String>();
+    let separator = " ----------- |".repeat(first.len());
+
+    println!();
+    println!("## {payload}: Size (bytes)");
+    println!();
+    println!("| {count_label:>9} |{header}");
+    println!("| --------- |{separator}");
+    for row in rows {
+        let cells = row
+            .iter()
+            .map(|point| format!(" {:>11} |", point.size))
+            .collect::<String>();
+        println!("| {:>9} |{cells}", row[0].count);
+    }
+
+    println!();
+    println!("## {payload}: Encode + Decode Time (µs)");
+    println!();
+    println!("| {count_label:>9} |{header}");
+    println!("| --------- |{separator}");
+    for row in rows {
+        let cells = row
+            .iter()
+            .map(|point| {
+                let micros = (point.encode + point.decode).as_secs_f64() * 1_000_000.0;
+                format!(" {micros:>11.1} |")
+            })
+            .collect::<String>();
+        println!("| {:>9} |{cells}", row[0].count);
+    }
+}
+
+fn text() {
+    println!("=== Escapes and Non-ASCII Text ===");
+
+    // One report per kind of text, each with a result per format.
+    let reports = Text::ALL.map(text_data::report);
+    let header = Text::ALL
+        .iter()
+        .map(|text| format!(" {:>9} |", text.name()))
+        .collect::<String>();
+    let separator = " --------- |".repeat(Text::ALL.len());
+
+    println!();
+    println!("## Borrowed (`&str`) Decode");
+    println!();
+    println!("| Format      |{header}");
+    println!("| ----------- |{separator}");
+    for (i, result) in reports[0].iter().enumerate() {
+        let cells = reports
+            .iter()
+            .map(|report| {
+                let cell = if report[i].borrowed.is_ok() {
+                    "ok"
+                } else {
+                    "fails"
+                };
+                format!(" {cell:>9} |")
+            })
+            .collect::<String>();
+        println!("| {:>11} |{cells}", result.format);
+    }
+
+    println!();
+    println!("## Owned (`String`) Encode + Decode Time vs ASCII");
+    println!();
+    println!("| Format      |{header}");
+    println!("| ----------- |{separator}");
+    for (i, result) in reports[0].iter().enumerate() {
+        let ascii = result.owned.as_ref().ok();
+        let cells = reports
+            .iter()
+            .map(|report| {
+                let cell = match (&report[i].owned, ascii) {
+                    (Ok(time), Some(ascii)) => {
+                        format!("{:.2}x", time.as_secs_f64() / ascii.as_secs_f64())
+                    }
+                    _ => "fails".to_string(),
+                };
+                format!(" {cell:>9} |")
+            })
+            .collect::<String>();
+        println!("| {:>11} |{cells}", result.format);
+    }
+
+    println!();
+    println!("## Failures");
+    println!();
+    for result in reports.iter().flatten() {
+        if let Err(e) = &result.borrowed {
+            println!(
+                "- {} / {} (borrowed): {e}",
+                result.format,
+                result.text.name()
+            );
+        }
+        if let Err(e) = &result.owned {
+            println!("- {} / {} (owned): {e}", result.format, result.text.name());
+        }
+    }
+}
+
+fn enums() {
+    println!("=== Enums and Optional Fields ===");
+
+    let report = event_data::report();
+    let header = Format::ALL
+        .iter()
+        .map(|format| format!(" {:>11} |", format.name()))
+        .collect::<String>();
+    let separator = " ----------- |".repeat(Format::ALL.len());
+
+    println!();
+    println!("## Round-trip");
+    println!();
+    println!("| Case               |{header}");
+    println!("| ------------------ |{separator}");
+    for (case, outcomes) in &report {
+        let cells = outcomes
+            .iter()
+            .map(|outcome| {
+                let cell = match outcome {
+                    Outcome::Ok => "ok",
+                    Outcome::Unsupported(_) => "unsupported",
+                    Outcome::Fails(_) => "fails",
+                };
+                format!(" {cell:>11} |")
+            })
+            .collect::<String>();
+        println!("| {case:<18} |{cells}");
+    }
+
+    // Sizes of the formats that can represent the whole stream.
+    let events = Event::stream(100);
+    let sizes = Format::ALL
+        .iter()
+        .filter_map(|format| {
+            format
+                .try_encode(&events)
+                .ok()
+                .map(|encoded| (format.name(), encoded.len()))
+        })
+        .collect::<Vec<_>>();
+    print_table("Event Stream (100 events)", &sizes);
+
+    println!();
+    println!("## Failures");
+    println!();
+    for (case, outcomes) in &report {
+        for (format, outcome) in Format::ALL.iter().zip(outcomes) {
+            match outcome {
+                Outcome::Ok => {}
+                Outcome::Unsupported(e) => {
+                    println!("- {} / {case} (unsupported): {e}", format.name())
+                }
+                Outcome::Fails(e) => println!("- {} / {case}: {e}", format.name()),
+            }
+        }
+    }
+}
+
+fn evolution() {
+    println!("=== Schema Evolution ===");
+    println!();
+    println!(
+        "Each change is a second version of the payload. Data written by one version is read by the \
+         other: `ok` if the reader gets the values it should, `misreads` if it decodes different \
+         values without an error, `fails` if encoding or decoding fails."
+    );
+
+    let report = evolution_data::report();
+    let header = Format::ALL
+        .iter()
+        .map(|format| format!(" {:>9} |", format.name()))
+        .collect::<String>();
+    let separator = " --------- |".repeat(Format::ALL.len());
+    let table = |title: &str, rows: &mut dyn Iterator<Item = (&str, String)>| {
+        println!();
+        println!("### {title}");
+        println!();
+        println!("| {:<20} |{header}", "Version");
+        println!("| -------------------- |{separator}");
+        for (version, cells) in rows {
+            println!("| {version:<20} |{cells}");
+        }
+    };
+    let sizes = |sizes: &[Result<usize, formats::Error>]| {
+        sizes
+            .iter()
+            .map(|size| match size {
+                Ok(size) => format!(" {size:>9} |"),
+                Err(_) => format!(" {:>9} |", "-"),
+            })
+            .collect::<String>()
+    };
+    let compatibility = |results: &[Compatibility]| {
+        results
+            .iter()
+            .map(|result| {
+                let cell = match result {
+                    Compatibility::Reads => "ok",
+                    Compatibility::Misreads => "misreads",
+                    Compatibility::Fails(_) => "fails",
+                };
+                format!(" {cell:>9} |")
+            })
+            .collect::<String>()
+    };
+
+    for evolution in &report {
+        println!();
+        println!("## {}", evolution.payload);
+
+        let original = iter::once(("Original", sizes(&evolution.sizes)));
+        let changed = evolution
+            .cases
+            .iter()
+            .map(|case| (case.change, sizes(&case.sizes)));
+        table("Size (bytes)", &mut original.chain(changed));
+        table(
+            "Old Reader, New Data",
+            &mut evolution
+                .cases
+                .iter()
+                .map(|case| (case.change, compatibility(&case.old_reads_new))),
+        );
+        table(
+            "New Reader, Old Data",
+            &mut evolution
+                .cases
+                .iter()
+                .map(|case| (case.change, compatibility(&case.new_reads_old))),
+        );
+    }
+
+    println!();
+    println!("## Failures");
+    println!();
+    for evolution in &report {
+        for case in &evolution.cases {
+            for (reader, results) in [
+                ("old reader", &case.old_reads_new),
+                ("new reader", &case.new_reads_old),
+            ] {
+                for (format, result) in Format::ALL.iter().zip(results) {
+                    if let Compatibility::Fails(e) = result {
+                        println!(
+                            "- {} / {} / {} ({reader}): {e}",
+                            format.name(),
+                            evolution.payload,
+                            case.change
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn blobs() {
+    println!("=== Binary Blobs ===");
+    println!();
+    println!("JSON encodes bytes as arrays of numbers:");
+    println!();
+    println!("```json");
+    println!("{}", blob_data::json_sample());
+    println!("```");
+
+    // One report per blob size, each with a result per format.
+    let reports = BLOB_SIZES.map(blob_data::report);
+    let header = BLOB_SIZES
+        .iter()
+        .map(|size| format!(" {size:>9} |"))
+        .collect::<String>();
+    let separator = " --------- |".repeat(BLOB_SIZES.len());
+    let table = |title: &str, cell: &dyn Fn(usize, usize) -> String| {
+        println!();
+        println!("## {title}");
+        println!();
+        println!("| Format      |{header}");
+        println!("| ----------- |{separator}");
+        for (i, result) in reports[0].iter().enumerate() {
+            let cells = (0..reports.len())
+                .map(|j| format!(" {:>9} |", cell(i, j)))
+                .collect::<String>();
+            println!("| {:>11} |{cells}", result.format);
+        }
+    };
+    // Rows of the baselines.
+    let row = |baseline| Format::ALL.iter().position(|f| *f == baseline).unwrap();
+    let (bson, bincode) = (row(Format::Bson), row(Format::Bincode));
+    let ratio = |i: usize, j: usize, baseline: usize| match (
+        &reports[j][i].encoded_size,
+        &reports[j][baseline].encoded_size,
+    ) {
+        (Ok(size), Ok(baseline)) => format!("{:.2}x", *size as f64 / *baseline as f64),
+        _ => "fails".to_string(),
+    };
+
+    table(
+        "Encoded Size (bytes) by Blob Size",
+        &|i, j| match &reports[j][i].encoded_size {
+            Ok(size) => size.to_string(),
+            Err(_) => "fails".to_string(),
+        },
+    );
+    table("Size vs BSON", &|i, j| ratio(i, j, bson));
+    table("Size vs Bincode", &|i, j| ratio(i, j, bincode));
+    table(
+        "Owned Encode + Decode Time (µs, 10 payloads)",
+        &|i, j| match &reports[j][i].owned {
+            Ok(time) => format!("{:.1}", time.as_secs_f64() * 1e6),
+            Err(_) => "fails".to_string(),
+        },
+    );
+    table("Borrowed (`&[u8]`) Decode", &|i, j| {
+        if reports[j][i].borrowed.is_ok() {
+            "ok".to_string()
+        } else {
+            "fails".to_string()
+        }
+    });
+
+    println!();
+    println!("## Failures");
+    println!();
+    for result in reports.iter().flatten() {
+        for (kind, error) in [
+            ("encode", result.encoded_size.as_ref().err()),
+            ("borrowed", result.borrowed.as_ref().err()),
+            ("owned", result.owned.as_ref().err()),
+        ] {
+            if let Some(e) = error {
+                println!(
+                    "- {} / {} bytes ({kind}): {e}",
+                    result.format, result.blob_size
+                );
+            }
+        }
+    }
+}
+
+fn tree() {
+    println!("=== Recursive Trees ===");
+
+    let trees = SHAPES
+        .map(|(depth, fan_out)| iter::repeat_n(Node::tree(depth, fan_out), 10).collect::<Vec<_>>());
+    let header = Format::ALL
+        .iter()
+        .map(|format| format!(" {:>9} |", format.name()))
+        .collect::<String>();
+    let separator = " --------- |".repeat(Format::ALL.len());
+    let table = |title: &str, cell: &dyn Fn(Format, &[Node]) -> Option<String>| {
+        println!();
+        println!("## {title}");
+        println!();
+        println!("| Depth × Fan-out (nodes) |{header}");
+        println!("| ----------------------- |{separator}");
+        for ((depth, fan_out), trees) in SHAPES.iter().zip(&trees) {
+            let cells = Format::ALL
+                .iter()
+                .map(|format| {
+                    let cell = cell(*format, trees).unwrap_or_else(|| "fails".to_string());
+                    format!(" {cell:>9} |")
+                })
+                .collect::<String>();
+            let shape = format!("{depth} × {fan_out} ({})", trees[0].count());
+            println!("| {shape:>23} |{cells}");
+        }
+    };
+
+    table("Encoded Size (bytes, 10 trees)", &|format, trees| {
+        format
+            .try_encode(trees)
+            .map(|encoded| encoded.len().to_string())
+            .ok()
+    });
+    table("Encode + Decode Time (µs, 10 trees)", &|format, trees| {
+        format.try_roundtrip(trees).ok()?;
+        let time = measure::time(|| format.try_roundtrip(trees));
+        Some(format!("{:.1}", time.as_secs_f64() * 1e6))
+    });
+
+    println!();
+    println!(
+        "## Nesting Limits (single-child chains, {} MiB stack)",
+        PROBE_STACK_SIZE / (1024 * 1024)
+    );
+    println!();
+    println!("| Format      | Deepest OK | Fails at | Stage    | Reason");
+    println!("| ----------- | ---------- | -------- | -------- | ------");
+    for format in Format::ALL {
+        // Double the depth until the round trip fails, then bisect.
+        let mut ok = 0;
+        let mut depth = 1;
+        let mut failure = None;
+        while depth <= MAX_PROBE_DEPTH {
+            match run_probe("roundtrip", format, depth) {
+                Ok(()) => ok = depth,
+                Err(reason) => {
+                    failure = Some((depth, reason));
+                    break;
+                }
+            }
+            depth *= 2;
+        }
+        let Some((mut failed, mut reason)) = failure else {
+            println!(
+                "| {:>11} | {:>10} | {:>8} | {:<8} | none up to {MAX_PROBE_DEPTH}",
+                format.name(),
+                ok,
+                "-",
+                "-"
+            );
+            continue;
+        };
+        while failed - ok > 1 {
+            let depth = ok + (failed - ok) / 2;
+            match run_probe("roundtrip", format, depth) {
+                Ok(()) => ok = depth,
+                Err(e) => (failed, reason) = (depth, e),
+            }
+        }
+        let stage = if run_probe("encode", format, failed).is_err() {
+            "encode"
+        } else {
+            "decode"
+        };
+
+        println!(
+            "| {:>11} | {:>10} | {:>8} | {:<8} | {reason}",
+            format.name(),
+            ok,
+            failed,
+            stage
+        );
+    }
+}
+
+fn floats() {
+    println!("=== Float Precision and Special Values ===");
+
+    let header = Format::ALL
+        .iter()
+        .map(|format| format!(" {:>9} |", format.name()))
+        .collect::<String>();
+    let separator = " --------- |".repeat(Format::ALL.len());
+    let cell = |result: &RoundTrip| {
+        let cell = match result {
+            RoundTrip::Exact => "exact",
+            RoundTrip::Changed(_) => "changed",
+            RoundTrip::Failed(_) => "fails",
+        };
+        format!(" {cell:>9} |")
+    };
+
+    let special = float_data::check_special_values();
+    println!();
+    println!("## Special Values (bit-exact round trip)");
+    println!();
+    println!("| Value             |{header}");
+    println!("| ----------------- |{separator}");
+    for (value, results) in &special {
+        let cells = results.iter().map(cell).collect::<String>();
+        println!("| {value:<17} |{cells}");
+    }
+
+    let payloads = [
+        ("Finite", NumericData::new(1000)),
+        ("With special", NumericData::with_special_values(1000)),
+    ];
+    let checks = payloads
+        .iter()
+        .map(|(name, data)| {
+            let results = Format::ALL
+                .iter()
+                .map(|format| float_data::check(*format, data))
+                .collect::<Vec<_>>();
+            (*name, results)
+        })
+        .collect::<Vec<_>>();
+    println!();
+    println!("## Numeric Payload (1000-value series, 100×1000 matrix)");
+    println!();
+    println!("| Values            |{header}");
+    println!("| ----------------- |{separator}");
+    for (name, results) in &checks {
+        let cells = results.iter().map(cell).collect::<String>();
+        println!("| {name:<17} |{cells}");
+    }
+
+    let data = std::slice::from_ref(&payloads[0].1);
+    let sizes = Format::ALL
+        .iter()
+        .filter_map(|format| Some((format.name(), format.try_encode(data).ok()?.len())))
+        .collect::<Vec<_>>();
+    print_table("Finite Payload", &sizes);
+
+    println!();
+    println!("## Failures");
+    println!();
+    let checks = checks
+        .into_iter()
+        .map(|(name, results)| (format!("{name} payload"), results));
+    for (value, results) in special.into_iter().chain(checks) {
+        for (format, result) in Format::ALL.iter().zip(results) {
+            match result {
+                RoundTrip::Exact => (),
+                RoundTrip::Changed(change) => println!("- {} / {value}: {change}", format.name()),
+                RoundTrip::Failed(e) => println!("- {} / {value}: {e}", format.name()),
+            }
+        }
+    }
+}
+
+fn wide() {
+    println!("=== Wide Integers, UUIDs and Timestamps ===");
+
+    let report = wide_data::report();
+    let header = Format::ALL
+        .iter()
+        .map(|format| format!(" {:>9} |", format.name()))
+        .collect::<String>();
+    let separator = " --------- |".repeat(Format::ALL.len());
+
+    println!();
+    println!("## Encoded Size ({RECORDS} records, bytes)");
+    println!();
+    println!("| Record            |{header}");
+    println!("| ----------------- |{separator}");
+    for case in &report {
+        let cells = case
+            .sizes
+            .iter()
+            .map(|size| match size {
+                Ok(size) => format!(" {size:>9} |"),
+                Err(_) => format!(" {:>9} |", "fails"),
+            })
+            .collect::<String>();
+        println!("| {:<17} |{cells}", case.name);
+    }
+
+    println!();
+    println!("## JSON Representation");
+    println!();
+    for case in &report {
+        println!("- {}: `{}`", case.name, case.json_sample);
+    }
+
+    println!();
+    println!("## Failures");
+    println!();
+    for case in &report {
+        for (format, size) in Format::ALL.iter().zip(&case.sizes) {
+            if let Err(e) = size {
+                println!("- {} / {}: {e}", format.name(), case.name);
+            }
+        }
+    }
+}
+
+fn columnar() {
+    println!("=== Rows vs Columns ===");
+
+    let report = columnar_data::report();
+    let header = Format::ALL
+        .iter()
+        .map(|format| format!(" {:>9} |", format.name()))
+        .collect::<String>();
+    let separator = " --------- |".repeat(Format::ALL.len());
+    let cell = |size: &Result<usize, _>| match size {
+        Ok(size) => format!(" {size:>9} |"),
+        Err(_) => format!(" {:>9} |", "fails"),
+    };
+
+    println!();
+    println!("## Encoded Size (10 instances, bytes)");
+    println!();
+    println!("| Payload         |{header}");
+    println!("| --------------- |{separator}");
+    for comparison in &report {
+        let rows = comparison.rows.iter().map(cell).collect::<String>();
+        let columns = comparison.columns.iter().map(cell).collect::<String>();
+        println!("| {:<15} |{rows}", format!("{} (rows)", comparison.payload));
+        println!(
+            "| {:<15} |{columns}",
+            format!("{} (columns)", comparison.payload)
+        );
+    }
+
+    println!();
+    println!("## Columns as a Percentage of Rows");
+    println!();
+    println!("| Payload         |{header}");
+    println!("| --------------- |{separator}");
+    for comparison in &report {
+        let cells = comparison
+            .rows
+            .iter()
+            .zip(&comparison.columns)
+            .map(|sizes| match sizes {
+                (Ok(rows), Ok(columns)) => {
+                    format!(" {:>8.1}% |", *columns as f64 / *rows as f64 * 100.0)
+                }
+                _ => format!(" {:>9} |", "-"),
+            })
+            .collect::<String>();
+        println!("| {:<15} |{cells}", comparison.payload);
+    }
+
+    println!();
+    println!("## Conversion");
+    println!();
+    for comparison in &report {
+        let lossless = if comparison.lossless {
+            "lossless"
+        } else {
+            "LOSSY"
+        };
+        println!("- {}: {lossless}", comparison.payload);
+    }
+
+    let failures = report.iter().flat_map(|comparison| {
+        Format::ALL
+            .iter()
+            .zip(comparison.rows.iter().zip(&comparison.columns))
+            .flat_map(move |(format, (rows, columns))| {
+                [("rows", rows), ("columns", columns)]
+                    .into_iter()
+                    .filter_map(move |(form, size)| {
+                        size.as_ref().err().map(|e| {
+                            format!("- {} / {} ({form}): {e}", format.name(), comparison.payload)
+                        })
+                    })
+            })
+    });
+    let failures = failures.collect::<Vec<_>>();
+    if !failures.is_empty() {
+        println!();
+        println!("## Failures");
+        println!();
+        for failure in failures {
+            println!("{failure}");
+        }
+    }
+}
+
+fn transforms() {
+    println!("=== Delta, Zigzag and Interning Pre-transforms ===");
+
+    let report = transform::report();
+    let name = |result: &transform::TransformResult| {
+        result
+            .transforms
+            .map_or("rows".to_string(), |transforms| transforms.name())
+    };
+    let header = Format::ALL
+        .iter()
+        .map(|format| format!(" {:>9} |", format.name()))
+        .collect::<String>();
+    let separator = " --------- |".repeat(Format::ALL.len());
+
+    println!();
+    println!("## Encoded Size (10 big vector instances, bytes)");
+    println!();
+    println!("| Transforms          |{header}");
+    println!("| ------------------- |{separator}");
+    for result in &report {
+        let cells = result
+            .sizes
+            .iter()
+            .map(|size| match size {
+                Ok(size) => format!(" {size:>9} |"),
+                Err(_) => format!(" {:>9} |", "fails"),
+            })
+            .collect::<String>();
+        println!("| {:<19} |{cells}", name(result));
+    }
+
+    println!();
+    println!("## Pack + Encode + Decode + Unpack Time vs Rows");
+    println!();
+    println!("| Transforms          |{header}");
+    println!("| ------------------- |{separator}");
+    for result in &report {
+        let cells = result
+            .times
+            .iter()
+            .zip(&report[0].times)
+            .map(|times| match times {
+                (Ok(time), Ok(rows)) => {
+                    format!(" {:>8.2}x |", time.as_secs_f64() / rows.as_secs_f64())
+                }
+                _ => format!(" {:>9} |", "fails"),
+            })
+            .collect::<String>();
+        println!("| {:<19} |{cells}", name(result));
+    }
+
+    println!();
+    println!("## Failures");
+    println!();
+    for result in &report {
+        if !result.lossless {
+            println!("- {}: doesn't unpack to the original data", name(result));
+        }
+        for (format, roundtrip) in Format::ALL.iter().zip(&result.roundtrips) {
+            if let Err(e) = roundtrip {
+                println!("- {} / {}: {e}", format.name(), name(result));
+            }
+        }
+    }
+}
+
+fn maps() {
+    println!("=== HashMap vs Ordered Maps and Sequences ===");
+
+    let header = Format::ALL
+        .iter()
+        .map(|format| format!(" {:>9} |", format.name()))
+        .collect::<String>();
+    let separator = " --------- |".repeat(Format::ALL.len());
+
+    println!();
+    println!("## Encoded Size (10 big instances, bytes)");
+    println!();
+    println!("| Maps       |{header}");
+    println!("| ---------- |{separator}");
+    let sizes = Maps::ALL.map(Maps::sizes);
+    for (maps, row) in Maps::ALL.iter().zip(&sizes) {
+        let cells = row
+            .iter()
+            .map(|size| match size {
+                Ok(size) => format!(" {size:>9} |"),
+                Err(_) => format!(" {:>9} |", "fails"),
+            })
+            .collect::<String>();
+        println!("| {:<10} |{cells}", maps.name());
+    }
+
+    // Whether the representation changes which formats are the most compact.
+    println!();
+    println!("## Size Rank (1 is the smallest)");
+    println!();
+    println!("| Maps       |{header}");
+    println!("| ---------- |{separator}");
+    for (maps, row) in Maps::ALL.iter().zip(&sizes) {
+        let cells = row
+            .iter()
+            .map(|size| match size {
+                Ok(size) => {
+                    let rank = 1 + row
+                        .iter()
+                        .filter(|other| other.as_ref().is_ok_and(|other| other < size))
+                        .count();
+                    format!(" {rank:>9} |")
+                }
+                Err(_) => format!(" {:>9} |", "-"),
+            })
+            .collect::<String>();
+        println!("| {:<10} |{cells}", maps.name());
+    }
+
+    println!();
+    println!("## Same Bytes From Two Processes");
+    println!();
+    println!("| Maps       |{header}");
+    println!("| ---------- |{separator}");
+    let mut failures = vec![];
+    for maps in Maps::ALL {
+        let cells = Format::ALL
+            .iter()
+            .map(|format| {
+                let cell = match (run_maps_probe(maps, *format), run_maps_probe(maps, *format)) {
+                    (Ok(first), Ok(second)) if first == second => "same",
+                    (Ok(_), Ok(_)) => "differs",
+                    (Err(e), _) | (_, Err(e)) => {
+                        failures.push(format!("- {} / {}: {e}", format.name(), maps.name()));
+                        "fails"
+                    }
+                };
+                format!(" {cell:>9} |")
+            })
+            .collect::<String>();
+        println!("| {:<10} |{cells}", maps.name());
+    }
+
+    println!();
+    println!("## Encode + Decode Time vs HashMap");
+    println!();
+    println!("| Maps       |{header}");
+    println!("| ---------- |{separator}");
+    let times = Maps::ALL.map(Maps::times);
+    for (maps, row) in Maps::ALL.iter().zip(&times) {
+        let cells = row
+            .iter()
+            .zip(&times[0])
+            .map(|times| match times {
+                (Ok(time), Ok(hash)) => {
+                    format!(" {:>8.2}x |", time.as_secs_f64() / hash.as_secs_f64())
+                }
+                _ => format!(" {:>9} |", "fails"),
+            })
+            .collect::<String>();
+        println!("| {:<10} |{cells}", maps.name());
+        for (format, time) in Format::ALL.iter().zip(row) {
+            if let Err(e) = time {
+                failures.push(format!("- {} / {}: {e}", format.name(), maps.name()));
+            }
+        }
+    }
+
+    if !failures.is_empty() {
+        println!();
+        println!("## Failures");
+        println!();
+        for failure in failures {
+            println!("{failure}");
+        }
+    }
+}
+
+fn import(path: &Path) {
+    let document = import::read(path).unwrap_or_else(|e| {
+        eprintln!("Failed to read {}: {e}", path.display());
+        process::exit(1);
+    });
+    let conversions = import::convert(&document);
+
+    println!("=== Encoded Size of {} ===", path.display());
+    println!();
+    println!(
+        "Bincode, Bitcode and Postcard need the type to decode, so they encode a tagged value enum."
+    );
+    println!("D-Bus encodes arrays of variants and `a{{sv}}` dicts.");
+
+    let sizes = conversions
+        .iter()
+        .filter_map(|conversion| Some((conversion.format, *conversion.size.as_ref().ok()?)))
+        .collect::<Vec<_>>();
+    if sizes.first().map(|(format, _)| *format) != Some("JSON") {
+        eprintln!("JSON round trip failed");
+        process::exit(1);
+    }
+    print_table("Document", &sizes);
+
+    let failures = conversions
+        .iter()
+        .filter_map(|conversion| Some((conversion.format, conversion.size.as_ref().err()?)))
+        .collect::<Vec<_>>();
+    if !failures.is_empty() {
+        println!();
+        println!("## Failures");
+        println!();
+        for (format, e) in failures {
+            println!("- {format}: {e}");
+        }
+    }
+}
+
+// Convert a payload (`big`, `small`, `big-vector` or `small-vector`) read from `path`, or standard
+// input, from one format to another, written to standard output.
+fn convert(payload: &str, from: Format, to: Format, path: Option<&Path>) {
+    let input = match path {
+        Some(path) => fs::read(path),
+        None => {
+            let mut input = vec![];
+            io::stdin().read_to_end(&mut input).map(|_| input)
+        }
+    }
+    .unwrap_or_else(|e| {
+        eprintln!("Failed to read the input: {e}");
+        process::exit(1);
+    });
+
+    let converted = match payload {
+        "big" => from.try_convert::<OwnedBigData>(&input, to),
+        "small" => from.try_convert::<OwnedSmallData>(&input, to),
+        "big-vector" => from.try_convert::<OwnedBigVectorData>(&input, to),
+        "small-vector" => from.try_convert::<SmallVectorData>(&input, to),
+        _ => usage(),
+    }
+    .unwrap_or_else(|e| {
+        eprintln!(
+            "Failed to convert from {} to {}: {e}",
+            from.name(),
+            to.name()
+        );
+        process::exit(1);
+    });
+
+    if let Err(e) = io::stdout().write_all(&converted) {
+        eprintln!("Failed to write the output: {e}");
+        process::exit(1);
+    }
+}
+
+// Run a `tree-probe` in a child process, returning why it failed.
+fn run_probe(stage: &str, format: Format, depth: usize) -> Result<(), String> {
+    let output = Command::new(env::current_exe().unwrap())
+        .args(["tree-probe", stage, format.name(), &depth.to_string()])
+        .output()
+        .unwrap();
+    if output.status.success() {
+        return Ok(());
+    }
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    if stderr.contains("overflowed its stack") {
+        Err("stack overflow".to_string())
+    } else {
+        Err(stderr.trim().to_string())
+    }
+}
+
+fn analyze() {
+    println!("=== Encoded Output Analysis ===");
+    println!();
+    println!(
+        "One instance of each payload, so repeats are within the payload rather than between \
+         copies. Ratios are with {}.",
+        analysis::REFERENCE_COMPRESSION
+    );
+
+    print_analysis("Big Payload", &analysis::report(&[BigData::new()]));
+    print_analysis("Small Payload", &analysis::report(&[SmallData::new()]));
+    print_analysis(
+        "Big Vector Payload",
+        &analysis::report(&[BigVectorData::new()]),
+    );
+    print_analysis(
+        "Small Vector Payload",
+        &analysis::report(&[SmallVectorData::new()]),
+    );
+}
+
+// The size table, followed by the statistics of the same outputs.
+fn print_analysis(title: &str, results: &[(&str, Result<Analysis, formats::Error>)]) {
+    let sizes = results
+        .iter()
+        .filter_map(|(format, analysis)| Some((*format, analysis.as_ref().ok()?.size)))
+        .collect::<Vec<_>>();
+    print_table(title, &sizes);
+
+    println!();
+    println!(
+        "| Format      | Entropy (bits/byte) | Zero bytes | Longest repeats (bytes) | Compressed |"
+    );
+    println!(
+        "| ----------- | ------------------- | ---------- | ----------------------- | ---------- |"
+    );
+    let mut failures = vec![];
+    for (format, analysis) in results {
+        match analysis {
+            Ok(analysis) => {
+                let repeats = analysis
+                    .repeats
+                    .iter()
+                    .map(|repeat| repeat.len.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                println!(
+                    "| {format:>11} | {:>19.2} | {:>9.1}% | {repeats:>23} | {:>9.1}% |",
+                    analysis.entropy,
+                    analysis.zeros * 100.0,
+                    analysis.compression_ratio * 100.0,
+                );
+            }
+            Err(e) => failures.push(format!("- {format} fails: {e}")),
+        }
+    }
+
+    if !failures.is_empty() {
+        println!();
+        println!("#### Failures");
+        println!();
+        for failure in failures {
+            println!("{failure}");
+        }
+    }
+}
+
+// Print a size table with ratios against the JSON row, or without if JSON isn't among `sizes`
+// (e.g. because it failed to encode).
+fn print_table(title: &str, sizes: &[(&str, usize)]) {
+    let json_size = sizes
+        .iter()
+        .find(|(format, _)| *format == Format::Json.name())
+        .map(|(_, size)| *size);
+
+    println!();
+    println!("### {title}");
+    println!();
+    println!("| Format      | Size (bytes) | Ratio vs JSON |");
+    println!("| ----------- | ------------ | ------------- |");
+    for (format, size) in sizes {
+        match json_size {
+            Some(json_size) => print_row(format, *size, json_size),
+            None => println!("| {:>11} | {:>12} | {:>13} |", format, size, "-"),
+        }
+    }
 }
 
 fn print_row(format: &str, size: usize, json_size: usize) {
@@ -261,3 +1639,214 @@ fn print_row(format: &str, size: usize, json_size: usize) {
         println!("| {:>11} | {:>12} | {:>12.1}% |", format, size, ratio);
     }
 }
+
+fn dictionary() {
+    let small_data = dictionary::small_data_report().unwrap_or_else(|e| {
+        eprintln!("{e}");
+        process::exit(1);
+    });
+
+    println!("=== Trained zstd Dictionaries for Small Messages ===");
+    println!();
+    println!(
+        "Dictionaries of up to {} bytes trained on {} messages, sizes and times averaged over {} \
+         other messages compressed one at a time.",
+        dictionary::DICTIONARY_SIZE,
+        dictionary::TRAINING_MESSAGES,
+        dictionary::TEST_MESSAGES,
+    );
+
+    print_dictionary("Seeded SmallData", &small_data);
+    print_dictionary("SmallVectorData", &dictionary::small_vector_report());
+}
+
+fn print_dictionary(payload: &str, results: &[Result<DictionaryResult, formats::Error>]) {
+    println!();
+    println!("## {payload}");
+    println!();
+    println!(
+        "| Format      |   raw |  zstd | zstd+dict | dict size | zstd (µs) | zstd+dict (µs) |"
+    );
+    println!(
+        "| ----------- | ----- | ----- | --------- | --------- | --------- | -------------- |"
+    );
+    let mut failures = vec![];
+    for (format, result) in Format::ALL.iter().zip(results) {
+        match result {
+            Ok(result) => println!(
+                "| {:>11} | {:>5.0} | {:>5.0} | {:>9.0} | {:>9} | {:>9.2} | {:>14.2} |",
+                result.format,
+                result.raw,
+                result.compressed,
+                result.with_dictionary,
+                result.dictionary_size,
+                result.time.as_secs_f64() * 1e6,
+                result.time_with_dictionary.as_secs_f64() * 1e6,
+            ),
+            Err(e) => failures.push(format!("- {} fails: {e}", format.name())),
+        }
+    }
+
+    if !failures.is_empty() {
+        println!();
+        println!("### Failures");
+        println!();
+        for failure in failures {
+            println!("{failure}");
+        }
+    }
+}
+
+fn hostile() {
+    let limits = Limits::from_env().unwrap_or_else(|e| {
+        eprintln!("{e}");
+        process::exit(1);
+    });
+
+    println!("=== Hostile Input ===");
+    println!();
+    println!(
+        "Each payload is decoded in a child process, without limits and with {limits}. Set `{}` to \
+         change them, e.g. `size=1048576,depth=32,len=10000`. Depth and length limits only apply to \
+         {}.",
+        limits::LIMITS_ENV,
+        Format::ALL
+            .iter()
+            .filter(|format| format.supports_limits())
+            .map(|format| format.name())
+            .collect::<Vec<_>>()
+            .join(", "),
+    );
+
+    for attack in Attack::ALL {
+        let title = match attack {
+            Attack::LengthBomb => format!(
+                "Length Bomb ({} readings claimed, none sent)",
+                hostile::CLAIMED_LEN
+            ),
+            Attack::DeepNesting => format!("Deep Nesting ({} levels)", hostile::NESTING_DEPTH),
+        };
+        println!();
+        println!("## {title}");
+        println!();
+        println!("| Format      | Limits    |  Input (bytes) | Result         | Peak memory (bytes) |  Time (ms) |");
+        println!("| ----------- | --------- | -------------- | -------------- | ------------------- | ---------- |");
+
+        let mut details = vec![];
+        for format in Format::ALL {
+            for limited in ["unlimited", "limited"] {
+                let probe = match run_hostile_probe(attack, format, limited) {
+                    Ok(probe) => probe,
+                    Err(e) => {
+                        details.push(format!("- {}: no payload, {e}", format.name()));
+                        break;
+                    }
+                };
+                let size = match probe.input_size {
+                    Some(size) => size.to_string(),
+                    None => "-".to_string(),
+                };
+                match probe.outcome {
+                    Ok((peak_memory, time, result)) => {
+                        let (outcome, reason) = match result.split_once(": ") {
+                            Some((outcome, reason)) => (outcome, Some(reason)),
+                            None => (result.as_str(), None),
+                        };
+                        println!(
+                            "| {:>11} | {limited:<9} | {size:>14} | {outcome:<14} | {peak_memory:>19} | {:>10.3} |",
+                            format.name(),
+                            time as f64 / 1e6,
+                        );
+                        if let Some(reason) = reason {
+                            details.push(format!("- {} ({limited}): {reason}", format.name()));
+                        }
+                    }
+                    Err(e) => {
+                        println!(
+                            "| {:>11} | {limited:<9} | {size:>14} | {e:<14} | {:>19} | {:>10} |",
+                            format.name(),
+                            "-",
+                            "-",
+                        );
+                    }
+                }
+            }
+        }
+
+        println!();
+        println!("### Errors");
+        println!();
+        for detail in details {
+            println!("{detail}");
+        }
+    }
+}
+
+// Exit code of `hostile-probe` when the attack has no payload for the format.
+const NO_PAYLOAD_EXIT: i32 = 2;
+
+// What `hostile-probe` printed: the input size, if it got that far, and the peak memory, time in
+// nanoseconds and result, or how it crashed.
+struct HostileProbe {
+    input_size: Option<usize>,
+    outcome: Result<(usize, u128, String), String>,
+}
+
+// Run `hostile-probe` in a child process, or return why the attack has no payload for the format.
+fn run_hostile_probe(
+    attack: Attack,
+    format: Format,
+    limited: &str,
+) -> Result<HostileProbe, String> {
+    let output = Command::new(env::current_exe().unwrap())
+        .args(["hostile-probe", attack.name(), format.name(), limited])
+        .output()
+        .unwrap();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    if output.status.code() == Some(NO_PAYLOAD_EXIT) {
+        return Err(stderr.trim().to_string());
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut lines = stdout.lines();
+    let input_size = lines.next().and_then(|size| size.parse().ok());
+    let parse = |line: &str| {
+        let mut fields = line.splitn(3, ' ');
+        Some((
+            fields.next()?.parse().ok()?,
+            fields.next()?.parse().ok()?,
+            fields.next()?.to_string(),
+        ))
+    };
+    let outcome = if stderr.contains("overflowed its stack") {
+        Err("stack overflow".to_string())
+    } else if !output.status.success() {
+        Err(match output.status.code() {
+            Some(_) => stderr.trim().to_string(),
+            None => "killed".to_string(),
+        })
+    } else {
+        lines
+            .next()
+            .and_then(parse)
+            .ok_or_else(|| "bad probe output".to_string())
+    };
+
+    Ok(HostileProbe {
+        input_size,
+        outcome,
+    })
+}
+
+// Encode the payload with `maps` in a child process and return the bytes it wrote.
+fn run_maps_probe(maps: Maps, format: Format) -> Result<Vec<u8>, String> {
+    let output = Command::new(env::current_exe().unwrap())
+        .args(["maps-probe", maps.name(), format.name()])
+        .output()
+        .unwrap();
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+
+    Ok(output.stdout)
+}
@@ -0,0 +1,21 @@
+use std::time::{Duration, Instant};
+
+// Each measurement is repeated until it has run for at least this long (at least once).
+const MEASUREMENT_TIME: Duration = Duration::from_millis(200);
+
+/// The mean time of `f`, run repeatedly for at least 200ms.
+///
+/// A quick alternative to the Criterion benchmarks for the reports of the `json-vs-bin` binary.
+pub fn time<O>(mut f: impl FnMut() -> O) -> Duration {
+    let mut iterations = 0u32;
+    let start = Instant::now();
+    loop {
+        drop(f());
+        iterations += 1;
+
+        let elapsed = start.elapsed();
+        if elapsed >= MEASUREMENT_TIME {
+            return elapsed / iterations;
+        }
+    }
+}
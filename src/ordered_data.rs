@@ -0,0 +1,339 @@
+//! Variants of [`BigData`] and [`SmallData`] with ordered maps or sequences instead of `HashMap`s.
+//!
+//! `HashMap`s iterate in a random order that changes with every process, so the same value
+//! encodes to different bytes from one run to the next in every format, which breaks hashing,
+//! caching and deduplication of encoded payloads. These variants use a `BTreeMap` (sorted by key)
+//! or an `IndexMap` (in insertion order) instead, at some cost in speed.
+//!
+//! Formats also encode maps differently from sequences (D-Bus as dict entries, BSON as documents
+//! with the keys as field names), so there are variants with `Vec<(&str, u32)>` and `Vec<Entry>`
+//! too, to see what the map representation itself costs.
+use crate::{
+    data::{BigData, SmallData},
+    formats::{DBusSignature, Encoded, Error, Format},
+    measure,
+};
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, hash::Hash, iter, time::Duration};
+use zvariant::{Signature, Type};
+
+/// The kind of map in a payload.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Maps {
+    Hash,
+    BTree,
+    Index,
+    /// `Vec<(&str, u32)>`
+    Pairs,
+    /// `Vec<Entry>`
+    Entries,
+}
+
+impl Maps {
+    pub const ALL: [Maps; 5] = [
+        Maps::Hash,
+        Maps::BTree,
+        Maps::Index,
+        Maps::Pairs,
+        Maps::Entries,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Maps::Hash => "HashMap",
+            Maps::BTree => "BTreeMap",
+            Maps::Index => "IndexMap",
+            Maps::Pairs => "Vec<pair>",
+            Maps::Entries => "Vec<Entry>",
+        }
+    }
+
+    /// Encode 10 instances of the big payload followed by 10 instances of the small one.
+    pub fn encode(self, format: Format) -> Result<Vec<u8>, Error> {
+        let big = iter::repeat_with(BigData::new).take(10).collect::<Vec<_>>();
+        let small = iter::repeat_with(SmallData::new)
+            .take(10)
+            .collect::<Vec<_>>();
+
+        let (mut encoded, small) = match self {
+            Maps::Hash => (format.try_encode(&big)?, format.try_encode(&small)?),
+            Maps::BTree => (
+                format.try_encode(&convert::<BTreeBigData, _>(&big))?,
+                format.try_encode(&convert::<BTreeSmallData, _>(&small))?,
+            ),
+            Maps::Index => (
+                format.try_encode(&convert::<IndexBigData, _>(&big))?,
+                format.try_encode(&convert::<IndexSmallData, _>(&small))?,
+            ),
+            Maps::Pairs => (
+                format.try_encode(&convert::<PairsBigData, _>(&big))?,
+                format.try_encode(&convert::<PairsSmallData, _>(&small))?,
+            ),
+            Maps::Entries => (
+                format.try_encode(&convert::<EntriesBigData, _>(&big))?,
+                format.try_encode(&convert::<EntriesSmallData, _>(&small))?,
+            ),
+        };
+        encoded.extend(small);
+
+        Ok(encoded)
+    }
+
+    /// Mean encode + decode time of 10 instances of the big payload with every format, in the
+    /// order of [`Format::ALL`].
+    pub fn times(self) -> Vec<Result<Duration, Error>> {
+        let big = iter::repeat_with(BigData::new).take(10).collect::<Vec<_>>();
+
+        match self {
+            Maps::Hash => {
+                roundtrip_times(&big, |encoded| Ok(encoded.try_decode::<BigData>()? == big))
+            }
+            Maps::BTree => {
+                let data = convert::<BTreeBigData, _>(&big);
+                roundtrip_times(&data, |encoded| {
+                    Ok(encoded.try_decode::<BTreeBigData>()? == data)
+                })
+            }
+            Maps::Index => {
+                let data = convert::<IndexBigData, _>(&big);
+                roundtrip_times(&data, |encoded| {
+                    Ok(encoded.try_decode::<IndexBigData>()? == data)
+                })
+            }
+            Maps::Pairs => {
+                let data = convert::<PairsBigData, _>(&big);
+                roundtrip_times(&data, |encoded| {
+                    Ok(encoded.try_decode::<PairsBigData>()? == data)
+                })
+            }
+            Maps::Entries => {
+                let data = convert::<EntriesBigData, _>(&big);
+                roundtrip_times(&data, |encoded| {
+                    Ok(encoded.try_decode::<EntriesBigData>()? == data)
+                })
+            }
+        }
+    }
+
+    /// Encoded size of 10 instances of the big payload with every format, in the order of
+    /// [`Format::ALL`].
+    pub fn sizes(self) -> Vec<Result<usize, Error>> {
+        let big = iter::repeat_with(BigData::new).take(10).collect::<Vec<_>>();
+
+        Format::ALL
+            .iter()
+            .map(|format| {
+                let encoded = match self {
+                    Maps::Hash => format.try_encode(&big),
+                    Maps::BTree => format.try_encode(&convert::<BTreeBigData, _>(&big)),
+                    Maps::Index => format.try_encode(&convert::<IndexBigData, _>(&big)),
+                    Maps::Pairs => format.try_encode(&convert::<PairsBigData, _>(&big)),
+                    Maps::Entries => format.try_encode(&convert::<EntriesBigData, _>(&big)),
+                };
+
+                Ok(encoded?.len())
+            })
+            .collect()
+    }
+}
+
+/// An [`IndexMap`], encoded like any other map (a dict in D-Bus).
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
+#[serde(transparent)]
+pub struct InsertionOrderMap<K: Hash + Eq, V>(pub IndexMap<K, V>);
+
+impl<K: Type + Hash + Eq, V: Type> Type for InsertionOrderMap<K, V> {
+    const SIGNATURE: &'static Signature = &Signature::static_dict(K::SIGNATURE, V::SIGNATURE);
+}
+
+impl<K: Hash + Eq, V> FromIterator<(K, V)> for InsertionOrderMap<K, V> {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+/// A map entry as a struct, a document in BSON and a struct in D-Bus.
+#[derive(Deserialize, Serialize, Type, PartialEq, Debug, Clone)]
+pub struct Entry<'a> {
+    #[serde(borrow)]
+    pub key: &'a str,
+    pub value: u32,
+}
+
+impl<'a> From<(&'a str, u32)> for Entry<'a> {
+    fn from((key, value): (&'a str, u32)) -> Self {
+        Self { key, value }
+    }
+}
+
+// Define big and small payloads with `$map` maps, convertible from the `HashMap` ones.
+macro_rules! map_payloads {
+    ($big:ident, $small:ident, $map:ty) => {
+        #[derive(Deserialize, Serialize, Type, PartialEq, Debug, Clone)]
+        pub struct $big<'a> {
+            pub user_id: u64,
+            pub status_code: u8,
+            pub is_active: bool,
+            #[serde(borrow)]
+            pub name: &'a str,
+            pub retry_count: u8,
+            #[serde(borrow)]
+            pub description: &'a str,
+            #[serde(borrow)]
+            pub properties: $map,
+            pub priority: u8,
+            #[serde(borrow)]
+            pub message: &'a str,
+            pub sequence_num: u32,
+            #[serde(borrow)]
+            pub metadata: $map,
+            pub session_id: u64,
+            pub error_code: u8,
+            pub is_verified: bool,
+            #[serde(borrow)]
+            pub title: &'a str,
+            pub attempt_count: u8,
+            #[serde(borrow)]
+            pub content: &'a str,
+            #[serde(borrow)]
+            pub attributes: $map,
+            pub level: u8,
+            #[serde(borrow)]
+            pub details: &'a str,
+            pub request_id: u32,
+            #[serde(borrow)]
+            pub headers: $map,
+            pub timestamp: u64,
+            pub response_code: u8,
+            pub is_complete: bool,
+            #[serde(borrow)]
+            pub summary: &'a str,
+            pub max_retries: u8,
+            #[serde(borrow)]
+            pub body: &'a str,
+            #[serde(borrow)]
+            pub tags: $map,
+        }
+
+        impl<'a> $big<'a> {
+            pub fn new() -> $big<'static> {
+                (&BigData::new()).into()
+            }
+        }
+
+        impl<'a> From<&BigData<'a>> for $big<'a> {
+            fn from(data: &BigData<'a>) -> Self {
+                Self {
+                    user_id: data.user_id,
+                    status_code: data.status_code,
+                    is_active: data.is_active,
+                    name: data.name,
+                    retry_count: data.retry_count,
+                    description: data.description,
+                    properties: in_insertion_order(&data.properties),
+                    priority: data.priority,
+                    message: data.message,
+                    sequence_num: data.sequence_num,
+                    metadata: in_insertion_order(&data.metadata),
+                    session_id: data.session_id,
+                    error_code: data.error_code,
+                    is_verified: data.is_verified,
+                    title: data.title,
+                    attempt_count: data.attempt_count,
+                    content: data.content,
+                    attributes: in_insertion_order(&data.attributes),
+                    level: data.level,
+                    details: data.details,
+                    request_id: data.request_id,
+                    headers: in_insertion_order(&data.headers),
+                    timestamp: data.timestamp,
+                    response_code: data.response_code,
+                    is_complete: data.is_complete,
+                    summary: data.summary,
+                    max_retries: data.max_retries,
+                    body: data.body,
+                    tags: in_insertion_order(&data.tags),
+                }
+            }
+        }
+
+        #[derive(Deserialize, Serialize, Type, PartialEq, Debug, Clone)]
+        pub struct $small<'a> {
+            pub id: u64,
+            pub status: u8,
+            pub enabled: bool,
+            #[serde(borrow)]
+            pub name: &'a str,
+            pub retries: u8,
+            #[serde(borrow)]
+            pub description: &'a str,
+            #[serde(borrow)]
+            pub config: $map,
+        }
+
+        impl<'a> $small<'a> {
+            pub fn new() -> $small<'static> {
+                (&SmallData::new()).into()
+            }
+        }
+
+        impl<'a> From<&SmallData<'a>> for $small<'a> {
+            fn from(data: &SmallData<'a>) -> Self {
+                Self {
+                    id: data.id,
+                    status: data.status,
+                    enabled: data.enabled,
+                    name: data.name,
+                    retries: data.retries,
+                    description: data.description,
+                    config: in_insertion_order(&data.config),
+                }
+            }
+        }
+    };
+}
+
+map_payloads!(BTreeBigData, BTreeSmallData, BTreeMap<&'a str, u32>);
+map_payloads!(
+    IndexBigData,
+    IndexSmallData,
+    InsertionOrderMap<&'a str, u32>
+);
+map_payloads!(PairsBigData, PairsSmallData, Vec<(&'a str, u32)>);
+map_payloads!(EntriesBigData, EntriesSmallData, Vec<Entry<'a>>);
+
+// The entries of a `HashMap` of the static payloads, in the order `BigData::new` and
+// `SmallData::new` inserted them in, which is the order of their values.
+fn in_insertion_order<'a, M, T>(map: &std::collections::HashMap<&'a str, u32>) -> M
+where
+    M: FromIterator<T>,
+    T: From<(&'a str, u32)>,
+{
+    let mut entries = map.iter().map(|(k, v)| (*k, *v)).collect::<Vec<_>>();
+    entries.sort_by_key(|(key, value)| (*value, *key));
+
+    entries.into_iter().map(T::from).collect()
+}
+
+fn convert<'a, T: From<&'a D>, D>(data: &'a [D]) -> Vec<T> {
+    data.iter().map(T::from).collect()
+}
+
+// Mean encode + decode time of `data` with every format, in the order of `Format::ALL`.
+// `decodes_to_data` decodes it back and compares it to `data`, as for
+// `Format::try_roundtrip_borrowed`.
+fn roundtrip_times<T: Serialize + DBusSignature + Clone>(
+    data: &[T],
+    decodes_to_data: impl Fn(&mut Encoded) -> Result<bool, Error>,
+) -> Vec<Result<Duration, Error>> {
+    Format::ALL
+        .into_iter()
+        .map(|format| {
+            let roundtrip = || format.try_roundtrip_borrowed(data, &decodes_to_data);
+            roundtrip()?;
+
+            Ok(measure::time(roundtrip))
+        })
+        .collect()
+}
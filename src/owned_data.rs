@@ -0,0 +1,576 @@
+//! Owned (`String`) and `Cow<str>` variants of the payloads in [`crate::data`] and
+//! [`crate::vector_data`].
+//!
+//! The borrowed payloads only decode when a format can hand out string slices of the input, which
+//! JSON can't do for strings with escapes. These variants show the cost when borrowing isn't
+//! possible (`Owned*`) or only possible some of the time (`Cow*`).
+//!
+//! Note that serde only borrows a `Cow<str>` when it's a direct field (marked `#[serde(borrow)]`),
+//! so the keys of the `Cow*` maps are always allocated.
+use crate::{
+    data::{BigData, SmallData},
+    vector_data::{BigVectorData, LogEvent, MarketTick, Metadata, SensorReading},
+};
+use serde::{Deserialize, Serialize};
+use std::{borrow::Cow, collections::HashMap};
+use zvariant::Type;
+
+#[derive(Deserialize, Serialize, Type, PartialEq, Debug, Clone)]
+pub struct OwnedBigData {
+    pub user_id: u64,
+    pub status_code: u8,
+    pub is_active: bool,
+    pub name: String,
+    pub retry_count: u8,
+    pub description: String,
+    pub properties: HashMap<String, u32>,
+    pub priority: u8,
+    pub message: String,
+    pub sequence_num: u32,
+    pub metadata: HashMap<String, u32>,
+    pub session_id: u64,
+    pub error_code: u8,
+    pub is_verified: bool,
+    pub title: String,
+    pub attempt_count: u8,
+    pub content: String,
+    pub attributes: HashMap<String, u32>,
+    pub level: u8,
+    pub details: String,
+    pub request_id: u32,
+    pub headers: HashMap<String, u32>,
+    pub timestamp: u64,
+    pub response_code: u8,
+    pub is_complete: bool,
+    pub summary: String,
+    pub max_retries: u8,
+    pub body: String,
+    pub tags: HashMap<String, u32>,
+}
+
+impl OwnedBigData {
+    pub fn new() -> Self {
+        Self::from(&BigData::new())
+    }
+
+    pub fn as_borrowed(&self) -> BigData<'_> {
+        BigData {
+            user_id: self.user_id,
+            status_code: self.status_code,
+            is_active: self.is_active,
+            name: &self.name,
+            retry_count: self.retry_count,
+            description: &self.description,
+            properties: borrow_map(&self.properties),
+            priority: self.priority,
+            message: &self.message,
+            sequence_num: self.sequence_num,
+            metadata: borrow_map(&self.metadata),
+            session_id: self.session_id,
+            error_code: self.error_code,
+            is_verified: self.is_verified,
+            title: &self.title,
+            attempt_count: self.attempt_count,
+            content: &self.content,
+            attributes: borrow_map(&self.attributes),
+            level: self.level,
+            details: &self.details,
+            request_id: self.request_id,
+            headers: borrow_map(&self.headers),
+            timestamp: self.timestamp,
+            response_code: self.response_code,
+            is_complete: self.is_complete,
+            summary: &self.summary,
+            max_retries: self.max_retries,
+            body: &self.body,
+            tags: borrow_map(&self.tags),
+        }
+    }
+}
+
+impl Default for OwnedBigData {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl From<&BigData<'_>> for OwnedBigData {
+    fn from(data: &BigData<'_>) -> Self {
+        Self {
+            user_id: data.user_id,
+            status_code: data.status_code,
+            is_active: data.is_active,
+            name: data.name.to_owned(),
+            retry_count: data.retry_count,
+            description: data.description.to_owned(),
+            properties: own_map(&data.properties),
+            priority: data.priority,
+            message: data.message.to_owned(),
+            sequence_num: data.sequence_num,
+            metadata: own_map(&data.metadata),
+            session_id: data.session_id,
+            error_code: data.error_code,
+            is_verified: data.is_verified,
+            title: data.title.to_owned(),
+            attempt_count: data.attempt_count,
+            content: data.content.to_owned(),
+            attributes: own_map(&data.attributes),
+            level: data.level,
+            details: data.details.to_owned(),
+            request_id: data.request_id,
+            headers: own_map(&data.headers),
+            timestamp: data.timestamp,
+            response_code: data.response_code,
+            is_complete: data.is_complete,
+            summary: data.summary.to_owned(),
+            max_retries: data.max_retries,
+            body: data.body.to_owned(),
+            tags: own_map(&data.tags),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Type, PartialEq, Debug, Clone)]
+pub struct OwnedSmallData {
+    pub id: u64,
+    pub status: u8,
+    pub enabled: bool,
+    pub name: String,
+    pub retries: u8,
+    pub description: String,
+    pub config: HashMap<String, u32>,
+}
+
+impl OwnedSmallData {
+    pub fn new() -> Self {
+        Self::from(&SmallData::new())
+    }
+
+    pub fn as_borrowed(&self) -> SmallData<'_> {
+        SmallData {
+            id: self.id,
+            status: self.status,
+            enabled: self.enabled,
+            name: &self.name,
+            retries: self.retries,
+            description: &self.description,
+            config: borrow_map(&self.config),
+        }
+    }
+}
+
+impl Default for OwnedSmallData {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl From<&SmallData<'_>> for OwnedSmallData {
+    fn from(data: &SmallData<'_>) -> Self {
+        Self {
+            id: data.id,
+            status: data.status,
+            enabled: data.enabled,
+            name: data.name.to_owned(),
+            retries: data.retries,
+            description: data.description.to_owned(),
+            config: own_map(&data.config),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Type, PartialEq, Debug, Clone)]
+pub struct OwnedMarketTick {
+    pub timestamp: u64,
+    pub symbol: String,
+    pub bid: f64,
+    pub ask: f64,
+    pub last: f64,
+    pub volume: u64,
+    pub bid_size: u32,
+    pub ask_size: u32,
+}
+
+impl OwnedMarketTick {
+    pub fn as_borrowed(&self) -> MarketTick<'_> {
+        MarketTick {
+            timestamp: self.timestamp,
+            symbol: &self.symbol,
+            bid: self.bid,
+            ask: self.ask,
+            last: self.last,
+            volume: self.volume,
+            bid_size: self.bid_size,
+            ask_size: self.ask_size,
+        }
+    }
+}
+
+impl From<&MarketTick<'_>> for OwnedMarketTick {
+    fn from(tick: &MarketTick<'_>) -> Self {
+        Self {
+            timestamp: tick.timestamp,
+            symbol: tick.symbol.to_owned(),
+            bid: tick.bid,
+            ask: tick.ask,
+            last: tick.last,
+            volume: tick.volume,
+            bid_size: tick.bid_size,
+            ask_size: tick.ask_size,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Type, PartialEq, Debug, Clone)]
+pub struct OwnedLogEvent {
+    pub timestamp: u64,
+    pub level: u8,
+    pub component: String,
+    pub message: String,
+    pub trace_id: u64,
+    pub span_id: u64,
+    pub user_id: u32,
+}
+
+impl OwnedLogEvent {
+    pub fn as_borrowed(&self) -> LogEvent<'_> {
+        LogEvent {
+            timestamp: self.timestamp,
+            level: self.level,
+            component: &self.component,
+            message: &self.message,
+            trace_id: self.trace_id,
+            span_id: self.span_id,
+            user_id: self.user_id,
+        }
+    }
+}
+
+impl From<&LogEvent<'_>> for OwnedLogEvent {
+    fn from(event: &LogEvent<'_>) -> Self {
+        Self {
+            timestamp: event.timestamp,
+            level: event.level,
+            component: event.component.to_owned(),
+            message: event.message.to_owned(),
+            trace_id: event.trace_id,
+            span_id: event.span_id,
+            user_id: event.user_id,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Type, PartialEq, Debug, Clone)]
+pub struct OwnedMetadata {
+    pub version: u16,
+    pub source: String,
+    pub created_at: u64,
+    pub batch_id: u64,
+    pub compression: bool,
+    pub checksum: u32,
+}
+
+impl OwnedMetadata {
+    pub fn as_borrowed(&self) -> Metadata<'_> {
+        Metadata {
+            version: self.version,
+            source: &self.source,
+            created_at: self.created_at,
+            batch_id: self.batch_id,
+            compression: self.compression,
+            checksum: self.checksum,
+        }
+    }
+}
+
+impl From<&Metadata<'_>> for OwnedMetadata {
+    fn from(metadata: &Metadata<'_>) -> Self {
+        Self {
+            version: metadata.version,
+            source: metadata.source.to_owned(),
+            created_at: metadata.created_at,
+            batch_id: metadata.batch_id,
+            compression: metadata.compression,
+            checksum: metadata.checksum,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Type, PartialEq, Debug, Clone)]
+pub struct OwnedBigVectorData {
+    pub sensors: Vec<SensorReading>,
+    pub market: Vec<OwnedMarketTick>,
+    pub logs: Vec<OwnedLogEvent>,
+    pub metadata: OwnedMetadata,
+}
+
+impl OwnedBigVectorData {
+    pub fn new() -> Self {
+        Self::from(&BigVectorData::new())
+    }
+
+    pub fn as_borrowed(&self) -> BigVectorData<'_> {
+        BigVectorData {
+            sensors: self.sensors.clone(),
+            market: self
+                .market
+                .iter()
+                .map(OwnedMarketTick::as_borrowed)
+                .collect(),
+            logs: self.logs.iter().map(OwnedLogEvent::as_borrowed).collect(),
+            metadata: self.metadata.as_borrowed(),
+        }
+    }
+}
+
+impl Default for OwnedBigVectorData {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl From<&BigVectorData<'_>> for OwnedBigVectorData {
+    fn from(data: &BigVectorData<'_>) -> Self {
+        Self {
+            sensors: data.sensors.clone(),
+            market: data.market.iter().map(OwnedMarketTick::from).collect(),
+            logs: data.logs.iter().map(OwnedLogEvent::from).collect(),
+            metadata: OwnedMetadata::from(&data.metadata),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Type, PartialEq, Debug, Clone)]
+pub struct CowBigData<'a> {
+    pub user_id: u64,
+    pub status_code: u8,
+    pub is_active: bool,
+    #[serde(borrow)]
+    pub name: Cow<'a, str>,
+    pub retry_count: u8,
+    #[serde(borrow)]
+    pub description: Cow<'a, str>,
+    pub properties: HashMap<Cow<'a, str>, u32>,
+    pub priority: u8,
+    #[serde(borrow)]
+    pub message: Cow<'a, str>,
+    pub sequence_num: u32,
+    pub metadata: HashMap<Cow<'a, str>, u32>,
+    pub session_id: u64,
+    pub error_code: u8,
+    pub is_verified: bool,
+    #[serde(borrow)]
+    pub title: Cow<'a, str>,
+    pub attempt_count: u8,
+    #[serde(borrow)]
+    pub content: Cow<'a, str>,
+    pub attributes: HashMap<Cow<'a, str>, u32>,
+    pub level: u8,
+    #[serde(borrow)]
+    pub details: Cow<'a, str>,
+    pub request_id: u32,
+    pub headers: HashMap<Cow<'a, str>, u32>,
+    pub timestamp: u64,
+    pub response_code: u8,
+    pub is_complete: bool,
+    #[serde(borrow)]
+    pub summary: Cow<'a, str>,
+    pub max_retries: u8,
+    #[serde(borrow)]
+    pub body: Cow<'a, str>,
+    pub tags: HashMap<Cow<'a, str>, u32>,
+}
+
+impl<'a> CowBigData<'a> {
+    pub fn new() -> CowBigData<'static> {
+        CowBigData::from(&BigData::new())
+    }
+}
+
+impl<'a> From<&BigData<'a>> for CowBigData<'a> {
+    fn from(data: &BigData<'a>) -> Self {
+        Self {
+            user_id: data.user_id,
+            status_code: data.status_code,
+            is_active: data.is_active,
+            name: Cow::Borrowed(data.name),
+            retry_count: data.retry_count,
+            description: Cow::Borrowed(data.description),
+            properties: cow_map(&data.properties),
+            priority: data.priority,
+            message: Cow::Borrowed(data.message),
+            sequence_num: data.sequence_num,
+            metadata: cow_map(&data.metadata),
+            session_id: data.session_id,
+            error_code: data.error_code,
+            is_verified: data.is_verified,
+            title: Cow::Borrowed(data.title),
+            attempt_count: data.attempt_count,
+            content: Cow::Borrowed(data.content),
+            attributes: cow_map(&data.attributes),
+            level: data.level,
+            details: Cow::Borrowed(data.details),
+            request_id: data.request_id,
+            headers: cow_map(&data.headers),
+            timestamp: data.timestamp,
+            response_code: data.response_code,
+            is_complete: data.is_complete,
+            summary: Cow::Borrowed(data.summary),
+            max_retries: data.max_retries,
+            body: Cow::Borrowed(data.body),
+            tags: cow_map(&data.tags),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Type, PartialEq, Debug, Clone)]
+pub struct CowSmallData<'a> {
+    pub id: u64,
+    pub status: u8,
+    pub enabled: bool,
+    #[serde(borrow)]
+    pub name: Cow<'a, str>,
+    pub retries: u8,
+    #[serde(borrow)]
+    pub description: Cow<'a, str>,
+    pub config: HashMap<Cow<'a, str>, u32>,
+}
+
+impl<'a> CowSmallData<'a> {
+    pub fn new() -> CowSmallData<'static> {
+        CowSmallData::from(&SmallData::new())
+    }
+}
+
+impl<'a> From<&SmallData<'a>> for CowSmallData<'a> {
+    fn from(data: &SmallData<'a>) -> Self {
+        Self {
+            id: data.id,
+            status: data.status,
+            enabled: data.enabled,
+            name: Cow::Borrowed(data.name),
+            retries: data.retries,
+            description: Cow::Borrowed(data.description),
+            config: cow_map(&data.config),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Type, PartialEq, Debug, Clone)]
+pub struct CowMarketTick<'a> {
+    pub timestamp: u64,
+    #[serde(borrow)]
+    pub symbol: Cow<'a, str>,
+    pub bid: f64,
+    pub ask: f64,
+    pub last: f64,
+    pub volume: u64,
+    pub bid_size: u32,
+    pub ask_size: u32,
+}
+
+impl<'a> From<&MarketTick<'a>> for CowMarketTick<'a> {
+    fn from(tick: &MarketTick<'a>) -> Self {
+        Self {
+            timestamp: tick.timestamp,
+            symbol: Cow::Borrowed(tick.symbol),
+            bid: tick.bid,
+            ask: tick.ask,
+            last: tick.last,
+            volume: tick.volume,
+            bid_size: tick.bid_size,
+            ask_size: tick.ask_size,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Type, PartialEq, Debug, Clone)]
+pub struct CowLogEvent<'a> {
+    pub timestamp: u64,
+    pub level: u8,
+    #[serde(borrow)]
+    pub component: Cow<'a, str>,
+    #[serde(borrow)]
+    pub message: Cow<'a, str>,
+    pub trace_id: u64,
+    pub span_id: u64,
+    pub user_id: u32,
+}
+
+impl<'a> From<&LogEvent<'a>> for CowLogEvent<'a> {
+    fn from(event: &LogEvent<'a>) -> Self {
+        Self {
+            timestamp: event.timestamp,
+            level: event.level,
+            component: Cow::Borrowed(event.component),
+            message: Cow::Borrowed(event.message),
+            trace_id: event.trace_id,
+            span_id: event.span_id,
+            user_id: event.user_id,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Type, PartialEq, Debug, Clone)]
+pub struct CowMetadata<'a> {
+    pub version: u16,
+    #[serde(borrow)]
+    pub source: Cow<'a, str>,
+    pub created_at: u64,
+    pub batch_id: u64,
+    pub compression: bool,
+    pub checksum: u32,
+}
+
+impl<'a> From<&Metadata<'a>> for CowMetadata<'a> {
+    fn from(metadata: &Metadata<'a>) -> Self {
+        Self {
+            version: metadata.version,
+            source: Cow::Borrowed(metadata.source),
+            created_at: metadata.created_at,
+            batch_id: metadata.batch_id,
+            compression: metadata.compression,
+            checksum: metadata.checksum,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Type, PartialEq, Debug, Clone)]
+pub struct CowBigVectorData<'a> {
+    pub sensors: Vec<SensorReading>,
+    #[serde(borrow)]
+    pub market: Vec<CowMarketTick<'a>>,
+    #[serde(borrow)]
+    pub logs: Vec<CowLogEvent<'a>>,
+    #[serde(borrow)]
+    pub metadata: CowMetadata<'a>,
+}
+
+impl<'a> CowBigVectorData<'a> {
+    pub fn new() -> CowBigVectorData<'static> {
+        CowBigVectorData::from(&BigVectorData::new())
+    }
+}
+
+impl<'a> From<&BigVectorData<'a>> for CowBigVectorData<'a> {
+    fn from(data: &BigVectorData<'a>) -> Self {
+        Self {
+            sensors: data.sensors.clone(),
+            market: data.market.iter().map(CowMarketTick::from).collect(),
+            logs: data.logs.iter().map(CowLogEvent::from).collect(),
+            metadata: CowMetadata::from(&data.metadata),
+        }
+    }
+}
+
+fn own_map(map: &HashMap<&str, u32>) -> HashMap<String, u32> {
+    map.iter().map(|(k, v)| (k.to_string(), *v)).collect()
+}
+
+fn borrow_map(map: &HashMap<String, u32>) -> HashMap<&str, u32> {
+    map.iter().map(|(k, v)| (k.as_str(), *v)).collect()
+}
+
+fn cow_map<'a>(map: &HashMap<&'a str, u32>) -> HashMap<Cow<'a, str>, u32> {
+    map.iter().map(|(k, v)| (Cow::Borrowed(*k), *v)).collect()
+}
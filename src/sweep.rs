@@ -0,0 +1,166 @@
+use crate::{data::SmallData, formats, measure::time, vector_data::BigVectorData};
+use std::{iter, time::Duration};
+use zvariant::serialized::Data;
+
+/// Numbers of [`SmallData`] instances in the sweep, from ~1.5 KB up to ~150 MB of JSON.
+pub const SMALL_COUNTS: [usize; 6] = [1, 10, 100, 1_000, 10_000, 100_000];
+
+/// Numbers of sensor records in the single [`BigVectorData`] instance of the sweep, from ~2.5 KB up
+/// to ~240 MB of JSON.
+pub const BIG_VECTOR_RECORDS: [usize; 6] = [10, 100, 1_000, 10_000, 100_000, 1_000_000];
+
+/// Environment variable capping the counts of the sweep benchmarks.
+pub const MAX_COUNT_ENV: &str = "JSON_VS_BIN_SWEEP_MAX";
+
+/// One point on a format's size/time curve.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Point {
+    pub format: &'static str,
+    /// Instance count for `SmallData`, record count for `BigVectorData`.
+    pub count: usize,
+    pub size: usize,
+    /// Mean time of a single encode.
+    pub encode: Duration,
+    /// Mean time of a single decode.
+    pub decode: Duration,
+}
+
+/// Run every format over `count` [`SmallData`] instances.
+pub fn small(count: usize) -> Vec<Point> {
+    let data = iter::repeat_with(SmallData::new)
+        .take(count)
+        .collect::<Vec<_>>();
+    let dbus = formats::DBus::new();
+    let bincode = formats::Bincode::new();
+
+    vec![
+        measure(
+            "JSON",
+            count,
+            || formats::Json::encode_small(&data),
+            |e| e.len(),
+            |e| drop(formats::Json::decode_small(e)),
+        ),
+        measure(
+            "SIMD-JSON",
+            count,
+            || formats::SimdJson::encode_small(&data),
+            |e| e.len(),
+            // SIMD-JSON decodes in place so it gets a fresh copy each time.
+            |e| drop(formats::SimdJson::decode_small(&mut e.clone())),
+        ),
+        measure(
+            "D-Bus",
+            count,
+            || dbus.encode_small(&data),
+            |e: &Data<'static, 'static>| e.bytes().len(),
+            |e| drop(dbus.decode_small(e)),
+        ),
+        measure(
+            "BSON",
+            count,
+            || formats::Bson::encode_small(&data),
+            |e| e.len(),
+            |e| drop(formats::Bson::decode_small(e)),
+        ),
+        measure(
+            "Bincode",
+            count,
+            || bincode.encode_small(&data),
+            |e| e.len(),
+            |e| drop(bincode.decode_small(e)),
+        ),
+        measure(
+            "Bitcode",
+            count,
+            || formats::Bitcode::encode_small(&data),
+            |e| e.len(),
+            |e| drop(formats::Bitcode::decode_small(e)),
+        ),
+        measure(
+            "Postcard",
+            count,
+            || formats::Postcard::encode_small(&data),
+            |e| e.len(),
+            |e| drop(formats::Postcard::decode_small(e)),
+        ),
+    ]
+}
+
+/// Run every format over a single [`BigVectorData`] instance with `records` sensor records.
+pub fn big_vector(records: usize) -> Vec<Point> {
+    // Wrapped in a one-element slice as the format API takes a batch of instances.
+    let data = [BigVectorData::with_records(records)];
+    let dbus = formats::DBus::new();
+    let bincode = formats::Bincode::new();
+
+    vec![
+        measure(
+            "JSON",
+            records,
+            || formats::Json::encode_big_vector(&data),
+            |e| e.len(),
+            |e| drop(formats::Json::decode_big_vector(e)),
+        ),
+        measure(
+            "SIMD-JSON",
+            records,
+            || formats::SimdJson::encode_big_vector(&data),
+            |e| e.len(),
+            |e| drop(formats::SimdJson::decode_big_vector(&mut e.clone())),
+        ),
+        measure(
+            "D-Bus",
+            records,
+            || dbus.encode_big_vector(&data),
+            |e: &Data<'static, 'static>| e.bytes().len(),
+            |e| drop(dbus.decode_big_vector(e)),
+        ),
+        measure(
+            "BSON",
+            records,
+            || formats::Bson::encode_big_vector(&data),
+            |e| e.len(),
+            |e| drop(formats::Bson::decode_big_vector(e)),
+        ),
+        measure(
+            "Bincode",
+            records,
+            || bincode.encode_big_vector(&data),
+            |e| e.len(),
+            |e| drop(bincode.decode_big_vector(e)),
+        ),
+        measure(
+            "Bitcode",
+            records,
+            || formats::Bitcode::encode_big_vector(&data),
+            |e| e.len(),
+            |e| drop(formats::Bitcode::decode_big_vector(e)),
+        ),
+        measure(
+            "Postcard",
+            records,
+            || formats::Postcard::encode_big_vector(&data),
+            |e| e.len(),
+            |e| drop(formats::Postcard::decode_big_vector(e)),
+        ),
+    ]
+}
+
+fn measure<E>(
+    format: &'static str,
+    count: usize,
+    mut encode: impl FnMut() -> E,
+    size: impl Fn(&E) -> usize,
+    mut decode: impl FnMut(&E),
+) -> Point {
+    let encoded = encode();
+
+    Point {
+        format,
+        count,
+        size: size(&encoded),
+        encode: time(&mut encode),
+        decode: time(|| decode(&encoded)),
+    }
+}
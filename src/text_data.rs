@@ -0,0 +1,142 @@
+//! Variants of [`BigData`] whose strings (values and map keys) need escaping in JSON or aren't
+//! ASCII, unlike the static payloads where JSON never has to escape anything.
+use crate::{
+    data::BigData,
+    formats::{Error, Format},
+    measure,
+    owned_data::OwnedBigData,
+};
+use std::{collections::HashMap, iter, time::Duration};
+
+// Length of the long text fields, same as the static payloads' `LONG_STRING`.
+const LONG_LEN: usize = 250;
+
+/// The kind of text put in the payload strings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Text {
+    /// Plain ASCII, as in the static payloads.
+    Ascii,
+    Quotes,
+    Backslashes,
+    /// Tabs, newlines, escape sequences and other control characters (except NUL).
+    Control,
+    /// Embedded NUL characters, which some formats can't represent in strings.
+    Nul,
+    Emoji,
+    /// Chinese, Japanese and Korean text.
+    Cjk,
+}
+
+impl Text {
+    pub const ALL: [Text; 7] = [
+        Text::Ascii,
+        Text::Quotes,
+        Text::Backslashes,
+        Text::Control,
+        Text::Nul,
+        Text::Emoji,
+        Text::Cjk,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Text::Ascii => "ASCII",
+            Text::Quotes => "Quotes",
+            Text::Backslashes => "Backslash",
+            Text::Control => "Control",
+            Text::Nul => "NUL",
+            Text::Emoji => "Emoji",
+            Text::Cjk => "CJK",
+        }
+    }
+
+    /// A short fragment of this kind of text, used as-is for short fields and as key prefix.
+    pub fn fragment(self) -> &'static str {
+        match self {
+            Text::Ascii => "oooooo",
+            Text::Quotes => r#"say "hi" "#,
+            Text::Backslashes => r"C:\tmp\x ",
+            Text::Control => "a\tb\r\n\u{1b}[0m\u{7}\u{7f}",
+            Text::Nul => "a\0b\0",
+            Text::Emoji => "😀🚀✨👍🏽",
+            Text::Cjk => "数据格式日本語한국어",
+        }
+    }
+
+    /// The fragment repeated to (at least) the length of the long text fields.
+    pub fn long(self) -> String {
+        let fragment = self.fragment();
+
+        fragment.repeat(LONG_LEN.div_ceil(fragment.len()))
+    }
+}
+
+/// [`BigData::new`] with every string field and map key rewritten to contain `text`.
+pub fn big_data(text: Text) -> OwnedBigData {
+    let fragment = text.fragment();
+    let long = text.long();
+    let keys = |map: HashMap<String, u32>| {
+        map.into_iter()
+            .map(|(key, value)| (format!("{fragment}{key}"), value))
+            .collect()
+    };
+    let data = OwnedBigData::new();
+
+    OwnedBigData {
+        name: fragment.to_owned(),
+        description: long.clone(),
+        properties: keys(data.properties),
+        message: fragment.to_owned(),
+        metadata: keys(data.metadata),
+        title: fragment.to_owned(),
+        content: long.clone(),
+        attributes: keys(data.attributes),
+        details: long.clone(),
+        headers: keys(data.headers),
+        summary: fragment.to_owned(),
+        body: long,
+        tags: keys(data.tags),
+        ..data
+    }
+}
+
+/// How one format copes with one kind of text.
+#[derive(Debug)]
+pub struct TextResult {
+    pub format: &'static str,
+    pub text: Text,
+    /// Whether the payload round-trips as borrowed [`BigData`].
+    pub borrowed: Result<(), Error>,
+    /// Mean encode + decode time as [`OwnedBigData`], which doesn't need borrowing.
+    pub owned: Result<Duration, Error>,
+}
+
+/// Run 10 instances of `text`'s payload through every format.
+pub fn report(text: Text) -> Vec<TextResult> {
+    let owned = iter::repeat_with(|| big_data(text))
+        .take(10)
+        .collect::<Vec<_>>();
+    let borrowed = owned
+        .iter()
+        .map(OwnedBigData::as_borrowed)
+        .collect::<Vec<_>>();
+
+    Format::ALL
+        .into_iter()
+        .map(|f| {
+            let borrowed = f.try_roundtrip_borrowed(&borrowed, |encoded| {
+                Ok(encoded.try_decode::<BigData>()? == borrowed)
+            });
+            let owned = f
+                .try_roundtrip(&owned)
+                .map(|()| measure::time(|| f.try_roundtrip(&owned)));
+
+            TextResult {
+                format: f.name(),
+                text,
+                borrowed,
+                owned,
+            }
+        })
+        .collect()
+}
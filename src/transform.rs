@@ -0,0 +1,408 @@
+//! Optional pre-transforms of [`OwnedBigVectorData`], applied before any format encodes it and
+//! undone after decoding, to see how much of the work a format doesn't do can be done for it:
+//!
+//! - delta: sequence-like integer columns (timestamps, IDs, volumes, sizes) store the difference
+//!   from the previous value, with wrapping arithmetic, so negative differences become huge
+//!   unsigned numbers;
+//! - zigzag: delta-encoded values are zigzag-encoded (0, -1, 1, -2, ... to 0, 1, 2, 3, ...), so
+//!   small negative differences stay small for varint formats;
+//! - intern: the repeated strings (symbols, components, messages) are stored once in a table and
+//!   referenced by index.
+//!
+//! The payload is laid out in columns, as in [`crate::columnar_data`], so consecutive values of a
+//! field are next to each other.
+use crate::{
+    columnar_data::{column_len, SensorColumns},
+    formats::{Error, Format},
+    measure,
+    owned_data::{OwnedBigVectorData, OwnedLogEvent, OwnedMarketTick, OwnedMetadata},
+};
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, iter, time::Duration};
+use zvariant::Type;
+
+/// Which pre-transforms to apply. Zigzag only applies to delta-encoded values.
+#[derive(Deserialize, Serialize, Type, PartialEq, Eq, Debug, Clone, Copy, Default)]
+pub struct Transforms {
+    pub delta: bool,
+    pub zigzag: bool,
+    pub intern: bool,
+}
+
+impl Transforms {
+    pub const NONE: Self = Self {
+        delta: false,
+        zigzag: false,
+        intern: false,
+    };
+    pub const DELTA: Self = Self {
+        delta: true,
+        ..Self::NONE
+    };
+    pub const DELTA_ZIGZAG: Self = Self {
+        zigzag: true,
+        ..Self::DELTA
+    };
+    pub const INTERN: Self = Self {
+        intern: true,
+        ..Self::NONE
+    };
+    pub const ALL: Self = Self {
+        delta: true,
+        zigzag: true,
+        intern: true,
+    };
+
+    /// The combinations in the report.
+    pub const PRESETS: [Self; 5] = [
+        Self::NONE,
+        Self::DELTA,
+        Self::DELTA_ZIGZAG,
+        Self::INTERN,
+        Self::ALL,
+    ];
+
+    pub fn name(self) -> String {
+        let names = [
+            (self.delta, "delta"),
+            (self.delta && self.zigzag, "zigzag"),
+            (self.intern, "intern"),
+        ]
+        .into_iter()
+        .filter_map(|(applied, name)| applied.then_some(name))
+        .collect::<Vec<_>>();
+
+        if names.is_empty() {
+            "none".to_string()
+        } else {
+            names.join("+")
+        }
+    }
+}
+
+/// [`OwnedMarketTick`]s as columns. Symbols are in `symbols`, or in `symbol_ids` if interned.
+#[derive(Deserialize, Serialize, Type, PartialEq, Debug, Clone, Default)]
+pub struct PackedMarket {
+    pub timestamps: Vec<u64>,
+    pub symbols: Vec<String>,
+    pub symbol_ids: Vec<u32>,
+    pub bids: Vec<f64>,
+    pub asks: Vec<f64>,
+    pub lasts: Vec<f64>,
+    pub volumes: Vec<u64>,
+    pub bid_sizes: Vec<u32>,
+    pub ask_sizes: Vec<u32>,
+}
+
+/// [`OwnedLogEvent`]s as columns. Strings are in `components` and `messages`, or in
+/// `component_ids` and `message_ids` if interned.
+#[derive(Deserialize, Serialize, Type, PartialEq, Debug, Clone, Default)]
+pub struct PackedLogs {
+    pub timestamps: Vec<u64>,
+    pub levels: Vec<u8>,
+    pub components: Vec<String>,
+    pub component_ids: Vec<u32>,
+    pub messages: Vec<String>,
+    pub message_ids: Vec<u32>,
+    pub trace_ids: Vec<u64>,
+    pub span_ids: Vec<u64>,
+    pub user_ids: Vec<u32>,
+}
+
+/// [`OwnedBigVectorData`] with [`Transforms`] applied.
+#[derive(Deserialize, Serialize, Type, PartialEq, Debug, Clone)]
+pub struct PackedVectorData {
+    /// The transforms applied, so the payload can be unpacked on its own.
+    pub transforms: Transforms,
+    pub sensors: SensorColumns,
+    pub market: PackedMarket,
+    pub logs: PackedLogs,
+    pub metadata: OwnedMetadata,
+    /// The interned strings, empty if not interned.
+    pub strings: Vec<String>,
+}
+
+impl PackedVectorData {
+    pub fn pack(data: &OwnedBigVectorData, transforms: Transforms) -> Self {
+        let mut interner = Interner {
+            enabled: transforms.intern,
+            ..Interner::default()
+        };
+        let mut market = PackedMarket::default();
+        for tick in &data.market {
+            market.timestamps.push(tick.timestamp);
+            interner.push(&tick.symbol, &mut market.symbols, &mut market.symbol_ids);
+            market.bids.push(tick.bid);
+            market.asks.push(tick.ask);
+            market.lasts.push(tick.last);
+            market.volumes.push(tick.volume);
+            market.bid_sizes.push(tick.bid_size);
+            market.ask_sizes.push(tick.ask_size);
+        }
+        let mut logs = PackedLogs::default();
+        for event in &data.logs {
+            logs.timestamps.push(event.timestamp);
+            logs.levels.push(event.level);
+            interner.push(
+                &event.component,
+                &mut logs.components,
+                &mut logs.component_ids,
+            );
+            interner.push(&event.message, &mut logs.messages, &mut logs.message_ids);
+            logs.trace_ids.push(event.trace_id);
+            logs.span_ids.push(event.span_id);
+            logs.user_ids.push(event.user_id);
+        }
+
+        let mut packed = Self {
+            transforms,
+            sensors: data.sensors[..].into(),
+            market,
+            logs,
+            metadata: data.metadata.clone(),
+            strings: interner.strings,
+        };
+        packed.map_deltas(|column, transforms| column.encode(transforms));
+
+        packed
+    }
+
+    /// The original payload, or an error if the columns differ in length or an interned string
+    /// is missing.
+    pub fn unpack(mut self) -> Result<OwnedBigVectorData, Error> {
+        self.map_deltas(|column, transforms| column.decode(transforms));
+        let (market, logs) = (&self.market, &self.logs);
+        let intern = self.transforms.intern;
+        // The length of the column a string comes from, and the string at `i`.
+        let string_len = |strings: &[String], ids: &[u32]| match intern {
+            true => ids.len(),
+            false => strings.len(),
+        };
+        let string = |strings: &[String], ids: &[u32], i: usize| match intern {
+            true => self
+                .strings
+                .get(ids[i] as usize)
+                .cloned()
+                .ok_or_else(|| Error::from("interned id out of range")),
+            false => Ok(strings[i].clone()),
+        };
+        let market_len = column_len(&[
+            market.timestamps.len(),
+            string_len(&market.symbols, &market.symbol_ids),
+            market.bids.len(),
+            market.asks.len(),
+            market.lasts.len(),
+            market.volumes.len(),
+            market.bid_sizes.len(),
+            market.ask_sizes.len(),
+        ])?;
+        let logs_len = column_len(&[
+            logs.timestamps.len(),
+            logs.levels.len(),
+            string_len(&logs.components, &logs.component_ids),
+            string_len(&logs.messages, &logs.message_ids),
+            logs.trace_ids.len(),
+            logs.span_ids.len(),
+            logs.user_ids.len(),
+        ])?;
+
+        Ok(OwnedBigVectorData {
+            sensors: self.sensors.rows()?,
+            market: (0..market_len)
+                .map(|i| {
+                    Ok(OwnedMarketTick {
+                        timestamp: market.timestamps[i],
+                        symbol: string(&market.symbols, &market.symbol_ids, i)?,
+                        bid: market.bids[i],
+                        ask: market.asks[i],
+                        last: market.lasts[i],
+                        volume: market.volumes[i],
+                        bid_size: market.bid_sizes[i],
+                        ask_size: market.ask_sizes[i],
+                    })
+                })
+                .collect::<Result<_, Error>>()?,
+            logs: (0..logs_len)
+                .map(|i| {
+                    Ok(OwnedLogEvent {
+                        timestamp: logs.timestamps[i],
+                        level: logs.levels[i],
+                        component: string(&logs.components, &logs.component_ids, i)?,
+                        message: string(&logs.messages, &logs.message_ids, i)?,
+                        trace_id: logs.trace_ids[i],
+                        span_id: logs.span_ids[i],
+                        user_id: logs.user_ids[i],
+                    })
+                })
+                .collect::<Result<_, Error>>()?,
+            metadata: self.metadata.clone(),
+        })
+    }
+
+    // Apply `f` to the delta-encoded columns, if delta encoding is on.
+    fn map_deltas(&mut self, f: impl Fn(&mut dyn Delta, Transforms)) {
+        let transforms = self.transforms;
+        if !transforms.delta {
+            return;
+        }
+
+        f(&mut self.sensors.timestamps, transforms);
+        f(&mut self.sensors.sensor_ids, transforms);
+        f(&mut self.market.timestamps, transforms);
+        f(&mut self.market.volumes, transforms);
+        f(&mut self.market.bid_sizes, transforms);
+        f(&mut self.market.ask_sizes, transforms);
+        f(&mut self.logs.timestamps, transforms);
+        f(&mut self.logs.trace_ids, transforms);
+        f(&mut self.logs.span_ids, transforms);
+    }
+}
+
+/// Sizes and times of the rows or of one combination of transforms.
+#[derive(Debug)]
+pub struct TransformResult {
+    /// The transforms applied, `None` for the original rows.
+    pub transforms: Option<Transforms>,
+    /// Whether the payload unpacks to the original rows without going through a format.
+    pub lossless: bool,
+    /// Encoded sizes, in the order of [`Format::ALL`].
+    pub sizes: Vec<Result<usize, Error>>,
+    /// Mean pack + encode + decode + unpack times, in the order of [`Format::ALL`].
+    pub times: Vec<Result<Duration, Error>>,
+    /// Whether the rows come back unchanged, in the order of [`Format::ALL`].
+    pub roundtrips: Vec<Result<(), Error>>,
+}
+
+/// Run 10 instances of [`OwnedBigVectorData`] through every format, as rows and with every
+/// combination in [`Transforms::PRESETS`].
+pub fn report() -> Vec<TransformResult> {
+    let data = iter::repeat_with(OwnedBigVectorData::new)
+        .take(10)
+        .collect::<Vec<_>>();
+
+    iter::once(None)
+        .chain(Transforms::PRESETS.map(Some))
+        .map(|transforms| run(&data, transforms))
+        .collect()
+}
+
+fn run(data: &[OwnedBigVectorData], transforms: Option<Transforms>) -> TransformResult {
+    let pack = |transforms| {
+        data.iter()
+            .map(|data| PackedVectorData::pack(data, transforms))
+            .collect::<Vec<_>>()
+    };
+    let encode = |format: Format| match transforms {
+        None => format.try_encode(data),
+        Some(transforms) => format.try_encode(&pack(transforms)),
+    };
+    let roundtrip = |format: Format| -> Result<Vec<OwnedBigVectorData>, Error> {
+        let encoded = encode(format)?;
+        Ok(match transforms {
+            None => format.try_decode(&encoded)?,
+            Some(_) => format
+                .try_decode::<PackedVectorData>(&encoded)?
+                .into_iter()
+                .map(PackedVectorData::unpack)
+                .collect::<Result<_, _>>()?,
+        })
+    };
+    let lossless = transforms.is_none_or(|transforms| {
+        pack(transforms)
+            .into_iter()
+            .map(|packed| packed.unpack().ok())
+            .eq(data.iter().cloned().map(Some))
+    });
+
+    TransformResult {
+        transforms,
+        lossless,
+        sizes: Format::ALL
+            .iter()
+            .map(|format| Ok(encode(*format)?.len()))
+            .collect(),
+        times: Format::ALL
+            .iter()
+            .map(|format| {
+                roundtrip(*format)?;
+                Ok(measure::time(|| roundtrip(*format)))
+            })
+            .collect(),
+        roundtrips: Format::ALL
+            .iter()
+            .map(|format| {
+                if roundtrip(*format)? != data {
+                    return Err("decoded data differs from the original".into());
+                }
+
+                Ok(())
+            })
+            .collect(),
+    }
+}
+
+// The string table, if interning.
+#[derive(Default)]
+struct Interner {
+    enabled: bool,
+    strings: Vec<String>,
+    ids: HashMap<String, u32>,
+}
+
+impl Interner {
+    // Push the ID of `s` to `ids` if interning, or `s` itself to `strings` otherwise.
+    fn push(&mut self, s: &str, strings: &mut Vec<String>, ids: &mut Vec<u32>) {
+        if !self.enabled {
+            strings.push(s.to_owned());
+            return;
+        }
+
+        let id = *self.ids.entry(s.to_owned()).or_insert_with(|| {
+            self.strings.push(s.to_owned());
+            self.strings.len() as u32 - 1
+        });
+        ids.push(id);
+    }
+}
+
+// An integer column that can be delta and zigzag encoded in place.
+trait Delta {
+    fn encode(&mut self, transforms: Transforms);
+    fn decode(&mut self, transforms: Transforms);
+}
+
+macro_rules! impl_delta {
+    ($unsigned:ty, $signed:ty) => {
+        impl Delta for Vec<$unsigned> {
+            fn encode(&mut self, transforms: Transforms) {
+                let mut previous = 0;
+                for value in self.iter_mut() {
+                    let delta = value.wrapping_sub(previous);
+                    previous = *value;
+                    *value = match transforms.zigzag {
+                        true => {
+                            let delta = delta as $signed;
+                            ((delta << 1) ^ (delta >> (<$signed>::BITS - 1))) as $unsigned
+                        }
+                        false => delta,
+                    };
+                }
+            }
+
+            fn decode(&mut self, transforms: Transforms) {
+                let mut previous: $unsigned = 0;
+                for value in self.iter_mut() {
+                    let delta = match transforms.zigzag {
+                        true => (*value >> 1) ^ (*value & 1).wrapping_neg(),
+                        false => *value,
+                    };
+                    previous = previous.wrapping_add(delta);
+                    *value = previous;
+                }
+            }
+        }
+    };
+}
+
+impl_delta!(u32, i32);
+impl_delta!(u64, i64);
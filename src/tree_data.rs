@@ -0,0 +1,156 @@
+//! A recursive payload, like a document tree or an AST, as opposed to the at most two levels deep
+//! static payloads.
+//!
+//! Serde (de)serializes nested values recursively, so deep trees exhaust the stack unless the
+//! format enforces a nesting limit first. D-Bus can't describe recursive types at all: signatures
+//! are static and capped at 64 nested containers.
+use crate::formats::{DBusSignature, Error, Format};
+use serde::{Deserialize, Serialize};
+use std::{mem, thread};
+use zvariant::Signature;
+
+/// Tree shapes of the report, as `(depth, fan-out)`.
+pub const SHAPES: [(usize, usize); 4] = [(3, 32), (6, 6), (12, 2), (32, 1)];
+
+/// Stack size of the thread running the probes, the default of the main thread on Linux.
+pub const PROBE_STACK_SIZE: usize = 8 * 1024 * 1024;
+
+/// Deepest tree the probes should be run with.
+pub const MAX_PROBE_DEPTH: usize = 1 << 20;
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
+pub struct Node {
+    pub id: u32,
+    pub kind: String,
+    pub name: String,
+    pub value: i64,
+    pub children: Vec<Node>,
+}
+
+impl Node {
+    /// A full tree, `depth` nodes deep with `fan_out` children per inner node.
+    pub fn tree(depth: usize, fan_out: usize) -> Node {
+        let mut next_id = 0;
+
+        Self::subtree(depth, fan_out, &mut next_id)
+    }
+
+    /// A tree with one node per level, built without recursion so it can be arbitrarily deep. As
+    /// with [`Node::tree`], a `depth` of 0 gives a single leaf.
+    pub fn chain(depth: usize) -> Node {
+        let depth = depth.max(1) as u32;
+        let mut node = Self::leaf(depth - 1);
+        for id in (0..depth - 1).rev() {
+            node = Node {
+                children: vec![node],
+                ..Self::element(id)
+            };
+        }
+
+        node
+    }
+
+    /// Number of levels, computed without recursion.
+    pub fn depth(&self) -> usize {
+        let mut depth = 0;
+        let mut level = vec![self];
+        while !level.is_empty() {
+            depth += 1;
+            level = level.iter().flat_map(|node| &node.children).collect();
+        }
+
+        depth
+    }
+
+    /// Number of nodes.
+    pub fn count(&self) -> usize {
+        1 + self.children.iter().map(Node::count).sum::<usize>()
+    }
+
+    fn subtree(depth: usize, fan_out: usize, next_id: &mut u32) -> Node {
+        let id = *next_id;
+        *next_id += 1;
+        if depth <= 1 {
+            return Self::leaf(id);
+        }
+
+        Node {
+            children: (0..fan_out)
+                .map(|_| Self::subtree(depth - 1, fan_out, next_id))
+                .collect(),
+            ..Self::element(id)
+        }
+    }
+
+    fn element(id: u32) -> Node {
+        Node {
+            id,
+            kind: "element".to_string(),
+            name: format!("section-{id}"),
+            value: id as i64 * 31,
+            children: vec![],
+        }
+    }
+
+    fn leaf(id: u32) -> Node {
+        Node {
+            kind: "text".to_string(),
+            ..Self::element(id)
+        }
+    }
+}
+
+impl DBusSignature for Node {
+    fn dbus_signature() -> Result<Signature, Error> {
+        Err("recursive types have no D-Bus signature".into())
+    }
+}
+
+/// Encode a [`Node::chain`] of `depth` with `format`, on a thread with a stack of
+/// [`PROBE_STACK_SIZE`].
+///
+/// Formats without a nesting limit overflow the stack on deep enough trees, which aborts the
+/// process, so this is meant to be run in a child process. Same for [`probe_roundtrip`].
+pub fn probe_encode(format: Format, depth: usize) -> Result<(), Error> {
+    on_probe_thread(move || {
+        let tree = Node::chain(depth);
+        let result = format.try_encode(std::slice::from_ref(&tree)).map(drop);
+        // Dropping deep trees recurses too.
+        mem::forget(tree);
+
+        result
+    })
+}
+
+/// Encode a [`Node::chain`] of `depth` with `format` and decode it back, on a thread with a stack
+/// of [`PROBE_STACK_SIZE`].
+pub fn probe_roundtrip(format: Format, depth: usize) -> Result<(), Error> {
+    on_probe_thread(move || {
+        let tree = Node::chain(depth);
+        let result = format
+            .try_encode(std::slice::from_ref(&tree))
+            .and_then(|encoded| {
+                let decoded = format.try_decode::<Node>(&encoded)?;
+                let decoded_depth = decoded.first().map(Node::depth);
+                mem::forget(decoded);
+
+                match decoded_depth {
+                    Some(decoded_depth) if decoded_depth == depth => Ok(()),
+                    _ => Err("decoded tree differs from the original".into()),
+                }
+            });
+        mem::forget(tree);
+
+        result
+    })
+}
+
+fn on_probe_thread(
+    probe: impl FnOnce() -> Result<(), Error> + Send + 'static,
+) -> Result<(), Error> {
+    thread::Builder::new()
+        .stack_size(PROBE_STACK_SIZE)
+        .spawn(probe)?
+        .join()
+        .map_err(|_| "probe thread panicked")?
+}
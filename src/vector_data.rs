@@ -78,9 +78,15 @@ pub struct SmallVectorData {
 
 impl<'a> BigVectorData<'a> {
     pub fn new() -> BigVectorData<'static> {
+        Self::with_records(1000)
+    }
+
+    /// Create an instance with `records` sensor readings, and half and a fifth as many market ticks
+    /// and log events respectively (as in [`BigVectorData::new`]).
+    pub fn with_records(records: usize) -> BigVectorData<'static> {
         let base_timestamp = 1_700_000_000_000_000u64;
 
-        let sensors: Vec<SensorReading> = (0..1000)
+        let sensors: Vec<SensorReading> = (0..records)
             .map(|i| SensorReading {
                 timestamp: base_timestamp + (i as u64 * 1000),
                 sensor_id: (i % 100) as u32,
@@ -92,10 +98,10 @@ impl<'a> BigVectorData<'a> {
             })
             .collect();
 
-        let symbols = vec![
+        let symbols = [
             "AAPL", "GOOGL", "MSFT", "AMZN", "META", "TSLA", "NVDA", "AMD",
         ];
-        let market: Vec<MarketTick> = (0..500)
+        let market: Vec<MarketTick> = (0..records / 2)
             .map(|i| {
                 let base_price = 100.0 + (i as f64 * 0.1);
                 MarketTick {
@@ -111,8 +117,8 @@ impl<'a> BigVectorData<'a> {
             })
             .collect();
 
-        let components = vec!["auth", "db", "api", "cache", "queue", "worker"];
-        let messages = vec![
+        let components = ["auth", "db", "api", "cache", "queue", "worker"];
+        let messages = [
             "Request processed successfully",
             "Connection established",
             "Cache miss, fetching from database",
@@ -121,7 +127,7 @@ impl<'a> BigVectorData<'a> {
             "Transaction committed",
         ];
 
-        let logs: Vec<LogEvent> = (0..200)
+        let logs: Vec<LogEvent> = (0..records / 5)
             .map(|i| LogEvent {
                 timestamp: base_timestamp + (i as u64 * 5000),
                 level: (i % 4) as u8,
@@ -183,3 +189,9 @@ impl SmallVectorData {
         }
     }
 }
+
+impl Default for SmallVectorData {
+    fn default() -> Self {
+        Self::new()
+    }
+}
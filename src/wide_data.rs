@@ -0,0 +1,222 @@
+//! Records with 128-bit integers, UUIDs and proper datetime types instead of raw `u64`s, and the
+//! different ways a timestamp can be serialized: `time`'s own format (a string in JSON, a tuple in
+//! the binary formats), RFC 3339 strings, Unix timestamps as integers and BSON's native datetime.
+use crate::{
+    formats::{self, DBusSignature, Error, Format},
+    generator::DEFAULT_SEED,
+};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use time::OffsetDateTime;
+use uuid::Uuid;
+use zvariant::{Signature, Type};
+
+/// Number of records of each case in the report.
+pub const RECORDS: usize = 100;
+
+/// The baseline: milliseconds since the epoch as a plain integer, as in the static payloads.
+#[derive(Deserialize, Serialize, Type, PartialEq, Debug, Clone)]
+pub struct UnixMillisRecord {
+    pub seq: u32,
+    pub at: u64,
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
+pub struct U128Record {
+    pub seq: u32,
+    pub id: u128,
+}
+
+impl DBusSignature for U128Record {
+    fn dbus_signature() -> Result<Signature, Error> {
+        Err("no 128-bit integers".into())
+    }
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
+pub struct I128Record {
+    pub seq: u32,
+    pub balance: i128,
+}
+
+impl DBusSignature for I128Record {
+    fn dbus_signature() -> Result<Signature, Error> {
+        Err("no 128-bit integers".into())
+    }
+}
+
+/// A UUID, a hyphenated string in human-readable formats and 16 bytes in the others.
+#[derive(Deserialize, Serialize, Type, PartialEq, Debug, Clone)]
+pub struct UuidRecord {
+    pub seq: u32,
+    pub id: Uuid,
+}
+
+/// `time`'s own serialization: a string in human-readable formats, a tuple of its fields in the
+/// others.
+#[derive(Deserialize, Serialize, Type, PartialEq, Debug, Clone)]
+pub struct DateTimeRecord {
+    pub seq: u32,
+    pub at: OffsetDateTime,
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
+pub struct Rfc3339Record {
+    pub seq: u32,
+    #[serde(with = "time::serde::rfc3339")]
+    pub at: OffsetDateTime,
+}
+
+impl DBusSignature for Rfc3339Record {
+    fn dbus_signature() -> Result<Signature, Error> {
+        Ok(<(u32, String)>::SIGNATURE.clone())
+    }
+}
+
+/// Nanoseconds since the epoch, which need an `i128`.
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
+pub struct UnixNanosRecord {
+    pub seq: u32,
+    #[serde(with = "time::serde::timestamp::nanoseconds")]
+    pub at: OffsetDateTime,
+}
+
+impl DBusSignature for UnixNanosRecord {
+    fn dbus_signature() -> Result<Signature, Error> {
+        Err("no 128-bit integers".into())
+    }
+}
+
+/// Milliseconds since the epoch as an `i64`, the same precision as BSON's datetime.
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
+pub struct UnixMillisDateTimeRecord {
+    pub seq: u32,
+    #[serde(with = "time::serde::timestamp::milliseconds_i64")]
+    pub at: OffsetDateTime,
+}
+
+impl DBusSignature for UnixMillisDateTimeRecord {
+    fn dbus_signature() -> Result<Signature, Error> {
+        Ok(<(u32, i64)>::SIGNATURE.clone())
+    }
+}
+
+/// BSON's native datetime, serialized as extended JSON (`{"$date": ...}`) by the other formats.
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
+pub struct BsonDateTimeRecord {
+    pub seq: u32,
+    pub at: bson::DateTime,
+}
+
+impl DBusSignature for BsonDateTimeRecord {
+    fn dbus_signature() -> Result<Signature, Error> {
+        Err("`bson::DateTime` has no D-Bus type".into())
+    }
+}
+
+/// How every format copes with one kind of record.
+#[derive(Debug)]
+pub struct Case {
+    pub name: &'static str,
+    /// The JSON encoding of the first record.
+    pub json_sample: String,
+    /// Encoded size of [`RECORDS`] records if they round-trip, in the order of [`Format::ALL`].
+    pub sizes: Vec<Result<usize, Error>>,
+}
+
+/// Run [`RECORDS`] records of every kind through every format.
+pub fn report() -> Vec<Case> {
+    let mut rng = ChaCha8Rng::seed_from_u64(DEFAULT_SEED);
+    // Millisecond precision, so that every representation can be lossless.
+    let times = (0..RECORDS)
+        .map(|_| {
+            let millis = rng.random_range(1_000_000_000_000i64..2_000_000_000_000);
+            OffsetDateTime::from_unix_timestamp_nanos(millis as i128 * 1_000_000).unwrap()
+        })
+        .collect::<Vec<_>>();
+
+    vec![
+        case(
+            "u64 millis",
+            records(&times, |seq, at| UnixMillisRecord {
+                seq,
+                at: (at.unix_timestamp_nanos() / 1_000_000) as u64,
+            }),
+        ),
+        case(
+            "u128",
+            records(&times, |seq, _| U128Record {
+                seq,
+                id: rng.random(),
+            }),
+        ),
+        case(
+            "i128",
+            records(&times, |seq, _| I128Record {
+                seq,
+                balance: rng.random(),
+            }),
+        ),
+        case(
+            "UUID",
+            records(&times, |seq, _| UuidRecord {
+                seq,
+                id: uuid::Builder::from_random_bytes(rng.random()).into_uuid(),
+            }),
+        ),
+        case(
+            "OffsetDateTime",
+            records(&times, |seq, at| DateTimeRecord { seq, at }),
+        ),
+        case(
+            "RFC 3339",
+            records(&times, |seq, at| Rfc3339Record { seq, at }),
+        ),
+        case(
+            "Unix nanos (i128)",
+            records(&times, |seq, at| UnixNanosRecord { seq, at }),
+        ),
+        case(
+            "Unix millis (i64)",
+            records(&times, |seq, at| UnixMillisDateTimeRecord { seq, at }),
+        ),
+        case(
+            "BSON datetime",
+            records(&times, |seq, at| BsonDateTimeRecord {
+                seq,
+                at: bson::DateTime::from_time_0_3(at),
+            }),
+        ),
+    ]
+}
+
+fn records<T>(times: &[OffsetDateTime], mut f: impl FnMut(u32, OffsetDateTime) -> T) -> Vec<T> {
+    times
+        .iter()
+        .enumerate()
+        .map(|(seq, at)| f(seq as u32, *at))
+        .collect()
+}
+
+fn case<T>(name: &'static str, records: Vec<T>) -> Case
+where
+    T: Serialize + DeserializeOwned + DBusSignature + Clone + PartialEq,
+{
+    let json_sample = formats::Json::try_encode(&records[..1])
+        .map(|json| String::from_utf8_lossy(&json[1..json.len() - 1]).into_owned())
+        .unwrap_or_else(|e| format!("({e})"));
+    let sizes = Format::ALL
+        .iter()
+        .map(|format| {
+            format.try_roundtrip(&records)?;
+            Ok(format.try_encode(&records)?.len())
+        })
+        .collect();
+
+    Case {
+        name,
+        json_sample,
+        sizes,
+    }
+}
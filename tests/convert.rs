@@ -0,0 +1,91 @@
+//! Conversion between formats: every payload encoded with each format, converted to each other
+//! format and decoded must equal the original exactly.
+//!
+//! Equality is structural, so maps compare by their entries whatever order a format writes them in.
+//! The one known lossy path is JSON's parsing of `f64`s (see "Floats" in the README): for payloads
+//! with full-precision `f64`s, conversions from or to JSON must change them, and all others must
+//! still be exact.
+use json_vs_bin::{
+    float_data::NumericData,
+    formats::{DBusSignature, Format},
+    generator::{Generator, GeneratorConfig},
+    owned_data::{OwnedBigData, OwnedBigVectorData, OwnedSmallData},
+    vector_data::SmallVectorData,
+};
+use serde::{de::DeserializeOwned, Serialize};
+use std::{fmt::Debug, iter};
+
+// Encode `data` with `from`, convert it to `to` and decode it.
+fn convert<T>(data: &[T], from: Format, to: Format) -> Vec<T>
+where
+    T: Serialize + DeserializeOwned + DBusSignature + Clone,
+{
+    let path = format!("{} to {}", from.name(), to.name());
+    let encoded = from
+        .try_encode(data)
+        .unwrap_or_else(|e| panic!("{path}: encode: {e}"));
+    let converted = from
+        .try_convert::<T>(&encoded, to)
+        .unwrap_or_else(|e| panic!("{path}: convert: {e}"));
+
+    to.try_decode(&converted)
+        .unwrap_or_else(|e| panic!("{path}: decode: {e}"))
+}
+
+// Whether converting between `from` and `to` loses full-precision `f64`s.
+fn lossy(from: Format, to: Format) -> bool {
+    from == Format::Json || to == Format::Json
+}
+
+// Check that `data` converts exactly between every pair of formats, or, if `has_full_f64s`, that
+// the lossy pairs change it.
+fn assert_converts<T>(data: &[T], has_full_f64s: bool)
+where
+    T: Serialize + DeserializeOwned + DBusSignature + Clone + PartialEq + Debug,
+{
+    for from in Format::ALL {
+        for to in Format::ALL.into_iter().filter(|to| *to != from) {
+            let converted = convert(data, from, to);
+            if has_full_f64s && lossy(from, to) {
+                assert_ne!(converted, data, "{} to {} is exact", from.name(), to.name());
+            } else {
+                assert_eq!(converted, data, "{} to {}", from.name(), to.name());
+            }
+        }
+    }
+}
+
+#[test]
+fn big_data_converts() {
+    let payloads = Generator::new(GeneratorConfig::default()).generate(10, 0);
+    assert_converts(payloads.owned_big_data(), false);
+    assert_converts(&[OwnedBigData::new()], false);
+}
+
+#[test]
+fn small_data_converts() {
+    let payloads = Generator::new(GeneratorConfig::default()).generate(0, 10);
+    assert_converts(payloads.owned_small_data(), false);
+    assert_converts(&[OwnedSmallData::new()], false);
+}
+
+#[test]
+fn big_vector_data_converts() {
+    let data = iter::repeat_with(OwnedBigVectorData::new)
+        .take(10)
+        .collect::<Vec<_>>();
+    assert_converts(&data, true);
+}
+
+#[test]
+fn small_vector_data_converts() {
+    let data = iter::repeat_with(SmallVectorData::new)
+        .take(10)
+        .collect::<Vec<_>>();
+    assert_converts(&data, false);
+}
+
+#[test]
+fn numeric_data_converts() {
+    assert_converts(&[NumericData::new(1000)], true);
+}
@@ -0,0 +1,214 @@
+//! Golden files: the encoding of fixed payloads with every format, committed in `tests/golden`, so
+//! that a dependency upgrade changing a wire format (as bincode 1 to 2 or bson 2 to 3 could) fails
+//! here before it ships.
+//!
+//! Each test fails if a format now encodes its payload differently from its golden file, or can't
+//! decode the golden file back to the payload any more. After checking that a change is intended,
+//! run `JSON_VS_BIN_BLESS=1 cargo test --test golden` to rewrite the files, and commit them.
+//!
+//! `HashMap`s iterate in a different order in every process, so the payloads use `BTreeMap`s, and
+//! floats are written out rather than computed, as `sin` and the like can differ across platforms.
+//! Strings have no escapes, which JSON couldn't decode into the borrowed payloads.
+use json_vs_bin::{
+    formats::{Encoded, Error, Format},
+    ordered_data::{BTreeBigData, BTreeSmallData},
+    vector_data::{
+        BigVectorData, LogEvent, MarketTick, Metadata, SensorReading, SmallVectorData, Summary,
+    },
+};
+use std::{env, fs, path::PathBuf};
+
+/// Set to rewrite the golden files from the current encodings instead of checking them.
+const BLESS_ENV: &str = "JSON_VS_BIN_BLESS";
+
+// Check the encoding of `$data` (a `Vec<$ty>`) with every format against the golden files named
+// after `$payload`, and that the golden files decode to `$data`. A macro as `$ty` may borrow from
+// the golden bytes.
+macro_rules! assert_golden {
+    ($payload:literal, $data:expr, $ty:ty) => {
+        let data: Vec<$ty> = $data;
+        let mut failures = vec![];
+        for format in Format::ALL {
+            let path = golden_path($payload, format);
+            let encoded = format.try_encode(&data).unwrap();
+            if env::var_os(BLESS_ENV).is_some() {
+                fs::write(&path, &encoded).unwrap();
+                continue;
+            }
+
+            let golden = match fs::read(&path) {
+                Ok(golden) => golden,
+                Err(e) => {
+                    failures.push(format!("{}: {e}", path.display()));
+                    continue;
+                }
+            };
+            if encoded != golden {
+                failures.push(format!(
+                    "{}: encoding changed: {}",
+                    format.name(),
+                    difference(&golden, &encoded)
+                ));
+            }
+
+            let mut golden = Encoded::new(format, golden);
+            let decoded: Result<Vec<$ty>, Error> = golden.try_decode();
+            match decoded {
+                Ok(decoded) if decoded == data => {}
+                Ok(_) => failures.push(format!(
+                    "{}: golden file decodes to a different payload",
+                    format.name()
+                )),
+                Err(e) => failures.push(format!(
+                    "{}: golden file no longer decodes: {e}",
+                    format.name()
+                )),
+            }
+        }
+
+        assert!(
+            failures.is_empty(),
+            "{}\nIf the change is intended, rerun with {BLESS_ENV}=1 and commit the golden files.",
+            failures.join("\n")
+        );
+    };
+}
+
+fn golden_path(payload: &str, format: Format) -> PathBuf {
+    [
+        env!("CARGO_MANIFEST_DIR"),
+        "tests",
+        "golden",
+        &format!("{payload}.{}", format.name().to_lowercase()),
+    ]
+    .iter()
+    .collect()
+}
+
+// Where `encoded` first differs from `golden`.
+fn difference(golden: &[u8], encoded: &[u8]) -> String {
+    let offset = golden
+        .iter()
+        .zip(encoded)
+        .position(|(golden, encoded)| golden != encoded)
+        .unwrap_or(golden.len().min(encoded.len()));
+
+    format!(
+        "{} bytes instead of {}, first difference at byte {offset}",
+        encoded.len(),
+        golden.len()
+    )
+}
+
+fn readings() -> Vec<SensorReading> {
+    vec![
+        SensorReading {
+            timestamp: 1_700_000_000_000_000,
+            sensor_id: 0,
+            temperature: 20.5,
+            humidity: 70.25,
+            pressure: 1013.25,
+            battery: 100,
+            status: 0,
+        },
+        SensorReading {
+            timestamp: 1_700_000_000_001_000,
+            sensor_id: 1,
+            temperature: -3.75,
+            humidity: 0.1,
+            pressure: 987.5,
+            battery: 42,
+            status: 3,
+        },
+    ]
+}
+
+fn big_vector_data() -> BigVectorData<'static> {
+    BigVectorData {
+        sensors: readings(),
+        market: vec![
+            MarketTick {
+                timestamp: 1_700_000_000_000_000,
+                symbol: "AAPL",
+                bid: 187.42,
+                ask: 187.44,
+                last: 187.43,
+                volume: 1_000_000,
+                bid_size: 100,
+                ask_size: 125,
+            },
+            MarketTick {
+                timestamp: 1_700_000_000_000_100,
+                symbol: "NVDA",
+                bid: 0.5,
+                ask: 1e-3,
+                last: 12345.678,
+                volume: i64::MAX as u64,
+                bid_size: 0,
+                ask_size: u32::MAX,
+            },
+        ],
+        logs: vec![
+            LogEvent {
+                timestamp: 1_700_000_000_000_000,
+                level: 1,
+                component: "auth",
+                message: "Request processed successfully",
+                trace_id: 1_000_000,
+                span_id: 2_000_000,
+                user_id: 1000,
+            },
+            LogEvent {
+                timestamp: 1_700_000_000_005_000,
+                level: 3,
+                component: "db",
+                message: "Caché miss – fetching from database",
+                trace_id: 1_000_001,
+                span_id: 2_000_002,
+                user_id: 0,
+            },
+        ],
+        metadata: Metadata {
+            version: 1,
+            source: "benchmark-system",
+            created_at: 1_700_000_000_000_000,
+            batch_id: 1_234_567_890,
+            compression: false,
+            checksum: 0x1234_5678,
+        },
+    }
+}
+
+fn small_vector_data() -> SmallVectorData {
+    SmallVectorData {
+        readings: readings(),
+        summary: Summary {
+            count: 2,
+            min_temp: -3.75,
+            max_temp: 20.5,
+            avg_temp: 8.375,
+            min_timestamp: 1_700_000_000_000_000,
+            max_timestamp: 1_700_000_000_001_000,
+        },
+    }
+}
+
+#[test]
+fn big_data_matches_golden() {
+    assert_golden!("big", vec![BTreeBigData::new()], BTreeBigData);
+}
+
+#[test]
+fn small_data_matches_golden() {
+    assert_golden!("small", vec![BTreeSmallData::new()], BTreeSmallData);
+}
+
+#[test]
+fn big_vector_data_matches_golden() {
+    assert_golden!("big_vector", vec![big_vector_data()], BigVectorData);
+}
+
+#[test]
+fn small_vector_data_matches_golden() {
+    assert_golden!("small_vector", vec![small_vector_data()], SmallVectorData);
+}
@@ -0,0 +1,76 @@
+//! Decode limits at their boundaries: every format that supports the depth and length limits must
+//! accept input right at a limit and reject input one over it.
+use json_vs_bin::{
+    formats::{DBusSignature, Format},
+    limits::Limits,
+};
+use serde::{de::DeserializeOwned, Serialize};
+use std::{collections::BTreeMap, fmt::Debug};
+
+const MAX: usize = 3;
+
+// Encode `data` with every format supporting the limits and decode it within `limits`, checking
+// that it's accepted (and decodes back to `data`) or rejected.
+fn assert_limited<T>(data: &[T], limits: Limits, accepted: bool)
+where
+    T: Serialize + DeserializeOwned + DBusSignature + Clone + PartialEq + Debug,
+{
+    for format in Format::ALL.into_iter().filter(|f| f.supports_limits()) {
+        let encoded = format.try_encode(data).unwrap();
+        let decoded = format.try_decode_with_limits::<T>(&encoded, &limits);
+        if accepted {
+            assert_eq!(decoded.unwrap(), data, "{}", format.name());
+        } else {
+            assert!(decoded.is_err(), "{} accepts {data:?}", format.name());
+        }
+    }
+}
+
+fn max_len() -> Limits {
+    Limits {
+        max_len: Some(MAX),
+        ..Limits::NONE
+    }
+}
+
+fn max_depth() -> Limits {
+    Limits {
+        max_depth: Some(MAX),
+        ..Limits::NONE
+    }
+}
+
+fn map(len: usize) -> BTreeMap<u32, u32> {
+    (0..len as u32).map(|i| (i, i)).collect()
+}
+
+#[test]
+fn sequence_at_max_len_is_accepted() {
+    assert_limited(&[0u32; MAX], max_len(), true);
+}
+
+#[test]
+fn sequence_over_max_len_is_rejected() {
+    assert_limited(&[0u32; MAX + 1], max_len(), false);
+}
+
+#[test]
+fn map_at_max_len_is_accepted() {
+    assert_limited(&[map(MAX)], max_len(), true);
+}
+
+#[test]
+fn map_over_max_len_is_rejected() {
+    assert_limited(&[map(MAX + 1)], max_len(), false);
+}
+
+// The outer sequence of the payload is the first level.
+#[test]
+fn nesting_at_max_depth_is_accepted() {
+    assert_limited(&[vec![vec![0u32]]], max_depth(), true);
+}
+
+#[test]
+fn nesting_over_max_depth_is_rejected() {
+    assert_limited(&[vec![vec![vec![0u32]]]], max_depth(), false);
+}
@@ -0,0 +1,367 @@
+//! Property tests: every format decodes what it encodes, for arbitrary payloads.
+//!
+//! The borrowed payloads (`BigData`, `BigVectorData`, ...) are decoded zero-copy as in the
+//! benchmarks, which JSON can only do for strings without escapes, so their strings never need
+//! one. The owned payloads take any string.
+//!
+//! BSON has no unsigned 64-bit integers, so it must refuse `u64`s above `i64::MAX` and round-trip
+//! everything else. JSON must decode `f64`s exactly as serde_json parses them on their own, the one
+//! known lossy path (see "Floats" in the README), and everything else exactly.
+use json_vs_bin::{
+    data::{BigData, SmallData},
+    formats::{Encoded, Error, Format},
+    owned_data::OwnedSmallData,
+    owned_data::{OwnedBigData, OwnedBigVectorData, OwnedLogEvent, OwnedMarketTick, OwnedMetadata},
+    vector_data::{BigVectorData, SensorReading, SmallVectorData, Summary},
+};
+use proptest::{array, collection, num, prelude::*};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+
+// Any value of an integer type, with the extremes more often than chance would give them.
+macro_rules! int {
+    ($ty:ty) => {
+        prop_oneof![Just(<$ty>::MIN), Just(<$ty>::MAX), any::<$ty>()]
+    };
+}
+
+// Encode `$data` (a slice) with every format and decode it zero-copy as `$ty`, expecting `$data`
+// back, or `$json_data` with JSON.
+macro_rules! prop_assert_roundtrips {
+    ($data:expr, $ty:ty) => {
+        prop_assert_roundtrips!($data, $data, $ty)
+    };
+    ($data:expr, $json_data:expr, $ty:ty) => {
+        for format in Format::ALL {
+            let encoded = format.try_encode($data);
+            if format == Format::Bson && !fits_i64($data) {
+                prop_assert!(encoded.is_err(), "BSON encodes u64s above i64::MAX");
+                continue;
+            }
+            let encoded = encoded
+                .map_err(|e| TestCaseError::fail(format!("{} encode: {e}", format.name())))?;
+            let mut encoded = Encoded::new(format, encoded);
+            let decoded: Result<Vec<$ty>, Error> = encoded.try_decode();
+            let decoded = decoded
+                .map_err(|e| TestCaseError::fail(format!("{} decode: {e}", format.name())))?;
+            let expected = if format == Format::Json {
+                $json_data
+            } else {
+                $data
+            };
+            prop_assert_eq!(decoded.as_slice(), expected, "{}", format.name());
+        }
+    };
+}
+
+// Whether every integer in `data` fits an `i64`.
+fn fits_i64<T: Serialize + ?Sized>(data: &T) -> bool {
+    fn fits(value: &Value) -> bool {
+        match value {
+            Value::Number(number) => !number.is_u64() || number.is_i64(),
+            Value::Array(values) => values.iter().all(fits),
+            Value::Object(values) => values.values().all(fits),
+            _ => true,
+        }
+    }
+
+    fits(&serde_json::to_value(data).unwrap())
+}
+
+// Strings JSON can decode zero-copy: nothing it has to escape.
+fn unescaped() -> impl Strategy<Value = String> + Clone {
+    "[^\"\\\\\\p{Cc}]{0,40}"
+}
+
+fn map(text: impl Strategy<Value = String>) -> impl Strategy<Value = HashMap<String, u32>> {
+    collection::hash_map(text, int!(u32), 0..8)
+}
+
+fn float32() -> impl Strategy<Value = f32> {
+    num::f32::POSITIVE
+        | num::f32::NEGATIVE
+        | num::f32::NORMAL
+        | num::f32::SUBNORMAL
+        | num::f32::ZERO
+}
+
+fn float64() -> impl Strategy<Value = f64> {
+    num::f64::POSITIVE
+        | num::f64::NEGATIVE
+        | num::f64::NORMAL
+        | num::f64::SUBNORMAL
+        | num::f64::ZERO
+}
+
+// `value` as serde_json decodes it.
+fn through_json(value: f64) -> f64 {
+    serde_json::from_str(&serde_json::to_string(&value).unwrap()).unwrap()
+}
+
+// `data` as JSON decodes it, with the `f64`s of its market ticks parsed by serde_json.
+fn json_big_vector_data(data: &OwnedBigVectorData) -> OwnedBigVectorData {
+    let mut data = data.clone();
+    for tick in &mut data.market {
+        for value in [&mut tick.bid, &mut tick.ask, &mut tick.last] {
+            *value = through_json(*value);
+        }
+    }
+
+    data
+}
+
+fn big_data(text: impl Strategy<Value = String> + Clone) -> impl Strategy<Value = OwnedBigData> {
+    (
+        array::uniform10(text.clone()),
+        array::uniform5(map(text)),
+        array::uniform3(int!(u64)),
+        array::uniform2(int!(u32)),
+        array::uniform8(int!(u8)),
+        array::uniform3(any::<bool>()),
+    )
+        .prop_map(|(strings, maps, u64s, u32s, u8s, bools)| {
+            let [name, description, message, title, content, details, summary, body, ..] =
+                strings.clone();
+            let [properties, metadata, attributes, headers, tags] = maps;
+
+            OwnedBigData {
+                user_id: u64s[0],
+                status_code: u8s[0],
+                is_active: bools[0],
+                name,
+                retry_count: u8s[1],
+                description,
+                properties,
+                priority: u8s[2],
+                message,
+                sequence_num: u32s[0],
+                metadata,
+                session_id: u64s[1],
+                error_code: u8s[3],
+                is_verified: bools[1],
+                title,
+                attempt_count: u8s[4],
+                content,
+                attributes,
+                level: u8s[5],
+                details,
+                request_id: u32s[1],
+                headers,
+                timestamp: u64s[2],
+                response_code: u8s[6],
+                is_complete: bools[2],
+                summary,
+                max_retries: u8s[7],
+                body,
+                tags,
+            }
+        })
+}
+
+fn small_data(
+    text: impl Strategy<Value = String> + Clone,
+) -> impl Strategy<Value = OwnedSmallData> {
+    (
+        int!(u64),
+        int!(u8),
+        any::<bool>(),
+        text.clone(),
+        int!(u8),
+        text.clone(),
+        map(text),
+    )
+        .prop_map(
+            |(id, status, enabled, name, retries, description, config)| OwnedSmallData {
+                id,
+                status,
+                enabled,
+                name,
+                retries,
+                description,
+                config,
+            },
+        )
+}
+
+fn sensor_reading() -> impl Strategy<Value = SensorReading> {
+    (
+        int!(u64),
+        int!(u32),
+        array::uniform3(float32()),
+        int!(u8),
+        int!(u8),
+    )
+        .prop_map(
+            |(timestamp, sensor_id, [temperature, humidity, pressure], battery, status)| {
+                SensorReading {
+                    timestamp,
+                    sensor_id,
+                    temperature,
+                    humidity,
+                    pressure,
+                    battery,
+                    status,
+                }
+            },
+        )
+}
+
+fn market_tick(text: impl Strategy<Value = String>) -> impl Strategy<Value = OwnedMarketTick> {
+    (
+        int!(u64),
+        text,
+        array::uniform3(float64()),
+        int!(u64),
+        int!(u32),
+        int!(u32),
+    )
+        .prop_map(
+            |(timestamp, symbol, [bid, ask, last], volume, bid_size, ask_size)| OwnedMarketTick {
+                timestamp,
+                symbol,
+                bid,
+                ask,
+                last,
+                volume,
+                bid_size,
+                ask_size,
+            },
+        )
+}
+
+fn log_event(text: impl Strategy<Value = String> + Clone) -> impl Strategy<Value = OwnedLogEvent> {
+    (
+        int!(u64),
+        int!(u8),
+        text.clone(),
+        text,
+        array::uniform2(int!(u64)),
+        int!(u32),
+    )
+        .prop_map(
+            |(timestamp, level, component, message, [trace_id, span_id], user_id)| OwnedLogEvent {
+                timestamp,
+                level,
+                component,
+                message,
+                trace_id,
+                span_id,
+                user_id,
+            },
+        )
+}
+
+fn metadata(text: impl Strategy<Value = String>) -> impl Strategy<Value = OwnedMetadata> {
+    (
+        int!(u16),
+        text,
+        array::uniform2(int!(u64)),
+        any::<bool>(),
+        int!(u32),
+    )
+        .prop_map(
+            |(version, source, [created_at, batch_id], compression, checksum)| OwnedMetadata {
+                version,
+                source,
+                created_at,
+                batch_id,
+                compression,
+                checksum,
+            },
+        )
+}
+
+fn big_vector_data(
+    text: impl Strategy<Value = String> + Clone,
+) -> impl Strategy<Value = OwnedBigVectorData> {
+    (
+        collection::vec(sensor_reading(), 0..8),
+        collection::vec(market_tick(text.clone()), 0..8),
+        collection::vec(log_event(text.clone()), 0..8),
+        metadata(text),
+    )
+        .prop_map(|(sensors, market, logs, metadata)| OwnedBigVectorData {
+            sensors,
+            market,
+            logs,
+            metadata,
+        })
+}
+
+fn small_vector_data() -> impl Strategy<Value = SmallVectorData> {
+    (
+        collection::vec(sensor_reading(), 0..8),
+        int!(u32),
+        array::uniform3(float32()),
+        array::uniform2(int!(u64)),
+    )
+        .prop_map(
+            |(readings, count, [min_temp, max_temp, avg_temp], [min_timestamp, max_timestamp])| {
+                SmallVectorData {
+                    readings,
+                    summary: Summary {
+                        count,
+                        min_temp,
+                        max_temp,
+                        avg_temp,
+                        min_timestamp,
+                        max_timestamp,
+                    },
+                }
+            },
+        )
+}
+
+proptest! {
+    #[test]
+    fn big_data_roundtrips(data in collection::vec(big_data(unescaped()), 0..4)) {
+        let data = data.iter().map(OwnedBigData::as_borrowed).collect::<Vec<_>>();
+        prop_assert_roundtrips!(&data[..], BigData);
+    }
+
+    #[test]
+    fn small_data_roundtrips(data in collection::vec(small_data(unescaped()), 0..4)) {
+        let data = data.iter().map(OwnedSmallData::as_borrowed).collect::<Vec<_>>();
+        prop_assert_roundtrips!(&data[..], SmallData);
+    }
+
+    #[test]
+    fn big_vector_data_roundtrips(data in collection::vec(big_vector_data(unescaped()), 0..4)) {
+        let json_data = data.iter().map(json_big_vector_data).collect::<Vec<_>>();
+        let json_data = json_data.iter().map(OwnedBigVectorData::as_borrowed).collect::<Vec<_>>();
+        let data = data.iter().map(OwnedBigVectorData::as_borrowed).collect::<Vec<_>>();
+        prop_assert_roundtrips!(&data[..], &json_data[..], BigVectorData);
+    }
+
+    #[test]
+    fn small_vector_data_roundtrips(data in collection::vec(small_vector_data(), 0..4)) {
+        prop_assert_roundtrips!(&data[..], SmallVectorData);
+    }
+
+    #[test]
+    fn owned_big_data_roundtrips(data in collection::vec(big_data(any::<String>()), 0..4)) {
+        prop_assert_roundtrips!(&data[..], OwnedBigData);
+    }
+
+    #[test]
+    fn owned_small_data_roundtrips(data in collection::vec(small_data(any::<String>()), 0..4)) {
+        prop_assert_roundtrips!(&data[..], OwnedSmallData);
+    }
+
+    #[test]
+    fn owned_big_vector_data_roundtrips(
+        data in collection::vec(big_vector_data(any::<String>()), 0..4),
+    ) {
+        let json_data = data.iter().map(json_big_vector_data).collect::<Vec<_>>();
+        prop_assert_roundtrips!(&data[..], &json_data[..], OwnedBigVectorData);
+    }
+}
+
+// The loss the round trips allow for: if serde_json decodes this exactly, it parses floats
+// exactly (e.g. `float_roundtrip` got enabled) and JSON should be held to exact round trips too.
+#[test]
+fn json_changes_some_f64s() {
+    let value = 2.5623677749493553e166;
+    assert_ne!(through_json(value).to_bits(), value.to_bits());
+}